@@ -1,13 +1,21 @@
 use std::fs;
 
 use anyhow::Error;
-use crosup_core::{config::verify_if_config_file_is_present, graph::build_installer_graph};
-use crosup_types::configuration::Configuration;
+use crosup_core::{
+    config::{default_configuration, verify_if_config_file_is_present},
+    graph::build_installer_graph,
+};
 use owo_colors::OwoColorize;
 
+use super::{get_database_connection, record_revision};
 use crate::{cmd::print_diff, macros::install, types::InstallArgs};
 
-pub async fn execute_add(tools: Vec<String>, ask: bool) -> Result<(), Error> {
+pub async fn execute_add(
+    tools: Vec<String>,
+    ask: bool,
+    message: Option<String>,
+    tags: Vec<String>,
+) -> Result<(), Error> {
     let (mut current_config, filename, content, is_present) = verify_if_config_file_is_present()?;
 
     current_config.packages = match current_config.packages {
@@ -36,10 +44,13 @@ pub async fn execute_add(tools: Vec<String>, ask: bool) -> Result<(), Error> {
         ..Default::default()
     };
 
-    install!(args, current_config, None);
+    let mut steps = vec![];
+    install!(args, current_config, None, steps);
 
     if is_present {
-        fs::write(filename, new_content)?;
+        fs::write(&filename, &new_content)?;
+        let db = get_database_connection().await?;
+        record_revision(&db, &filename, &new_content, message.as_deref(), &tags).await?;
     }
 
     Ok(())
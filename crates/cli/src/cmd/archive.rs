@@ -0,0 +1,382 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    path::Path,
+};
+
+use anyhow::Error;
+use crosup_entity::{file, modification, project, run, run_step};
+use crosup_repo::{
+    file::FileRepo, modification::ModificationRepo, project::ProjectRepo, run::RunRepo,
+    run_step::RunStepRepo,
+};
+use owo_colors::OwoColorize;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+
+use super::get_database_connection;
+
+const ARCHIVE_VERSION: u32 = 1;
+
+/// The whole history database, ids are only meaningful within the archive.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Archive {
+    version: u32,
+    projects: Vec<project::Model>,
+    files: Vec<file::Model>,
+    modifications: Vec<modification::Model>,
+    runs: Vec<run::Model>,
+    run_steps: Vec<run_step::Model>,
+}
+
+/// A line of an ndjson archive, e.g. `{"type": "file", "id": 1, ...}`, the
+/// first one holds the version of the archive.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record {
+    Header { version: u32 },
+    Project(project::Model),
+    File(file::Model),
+    Modification(modification::Model),
+    Run(run::Model),
+    RunStep(run_step::Model),
+}
+
+pub async fn execute_history_export(format: &str, output: Option<String>) -> Result<(), Error> {
+    let db: DatabaseConnection = get_database_connection().await?;
+
+    let archive = Archive {
+        version: ARCHIVE_VERSION,
+        projects: ProjectRepo::new(&db).find_all().await?,
+        files: FileRepo::new(&db).find_all().await?,
+        modifications: ModificationRepo::new(&db).find_all().await?,
+        runs: RunRepo::new(&db).find_all().await?,
+        run_steps: RunStepRepo::new(&db).find_all().await?,
+    };
+
+    let content = match format {
+        "json" => serde_json::to_string_pretty(&archive)? + "\n",
+        "ndjson" => to_ndjson(archive)?,
+        _ => {
+            return Err(Error::msg(format!(
+                "Unsupported format {}, expected json or ndjson",
+                format
+            )))
+        }
+    };
+
+    match output {
+        Some(output) => {
+            std::fs::write(&output, content)?;
+            eprintln!("History exported to {} ✨", output.bright_green());
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+pub async fn execute_history_import(path: &str) -> Result<(), Error> {
+    let content = match path {
+        "-" => {
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)?;
+            content
+        }
+        _ => std::fs::read_to_string(path)?,
+    };
+    let archive = parse_archive(&content)?;
+
+    let db: DatabaseConnection = get_database_connection().await?;
+
+    let mut projects = HashMap::new();
+    for project in archive.projects.iter() {
+        let id = ProjectRepo::new(&db)
+            .create(&project.host, &project.path)
+            .await?
+            .id;
+        projects.insert(project.id, id);
+    }
+
+    let mut files = HashMap::new();
+    for file in archive.files.iter() {
+        let project_id = match file.project_id.and_then(|id| projects.get(&id)) {
+            Some(id) => *id,
+            None => {
+                let directory = Path::new(&file.path).parent().unwrap_or(Path::new("/"));
+                ProjectRepo::new(&db)
+                    .create(&file.host, &directory.display().to_string())
+                    .await?
+                    .id
+            }
+        };
+        let id = FileRepo::new(&db)
+            .create(&file.host, &file.name, &file.path, project_id)
+            .await?
+            .id;
+        files.insert(file.id, id);
+    }
+
+    // revisions and runs already in the database are skipped, importing the
+    // same archive twice is harmless
+    let mut modifications = HashMap::new();
+    let mut imported_modifications = 0;
+    let mut touched_files = HashSet::new();
+    for modification in archive.modifications {
+        let file_id = match files.get(&modification.file_id) {
+            Some(id) => *id,
+            None => continue,
+        };
+        let existing = ModificationRepo::new(&db)
+            .find_by_file_id(file_id)
+            .await?
+            .into_iter()
+            .find(|m| m.hash == modification.hash && m.timestamp == modification.timestamp);
+        let id = match existing {
+            Some(existing) => existing.id,
+            None => {
+                imported_modifications += 1;
+                touched_files.insert(file_id);
+                ModificationRepo::new(&db)
+                    .insert(modification::Model {
+                        file_id,
+                        ..modification.clone()
+                    })
+                    .await?
+                    .id
+            }
+        };
+        modifications.insert(modification.id, id);
+    }
+    for file_id in touched_files {
+        ModificationRepo::new(&db).relink(file_id).await?;
+    }
+
+    let mut steps: HashMap<i32, Vec<run_step::Model>> = HashMap::new();
+    for step in archive.run_steps {
+        steps.entry(step.run_id).or_default().push(step);
+    }
+
+    let mut imported_runs = 0;
+    for run in archive.runs {
+        let file_id = match files.get(&run.file_id) {
+            Some(id) => *id,
+            None => continue,
+        };
+        let exists = RunRepo::new(&db)
+            .find_by_file_id(file_id)
+            .await?
+            .iter()
+            .any(|r| r.host == run.host && r.started_at == run.started_at);
+        if exists {
+            continue;
+        }
+        let model = RunRepo::new(&db)
+            .create(
+                file_id,
+                run.modification_id
+                    .and_then(|id| modifications.get(&id))
+                    .copied(),
+                &run.host,
+                &run.status,
+                run.started_at,
+                run.duration_ms,
+            )
+            .await?;
+        for step in steps.remove(&run.id).unwrap_or_default() {
+            RunStepRepo::new(&db)
+                .create(run_step::Model {
+                    run_id: model.id,
+                    ..step
+                })
+                .await?;
+        }
+        imported_runs += 1;
+    }
+
+    println!(
+        "Imported {} revision(s) and {} run(s) of {} file(s) ✨",
+        imported_modifications.bright_green(),
+        imported_runs.bright_green(),
+        files.len().bright_green()
+    );
+    Ok(())
+}
+
+fn to_ndjson(archive: Archive) -> Result<String, Error> {
+    let records = std::iter::once(Record::Header {
+        version: archive.version,
+    })
+    .chain(archive.projects.into_iter().map(Record::Project))
+    .chain(archive.files.into_iter().map(Record::File))
+    .chain(archive.modifications.into_iter().map(Record::Modification))
+    .chain(archive.runs.into_iter().map(Record::Run))
+    .chain(archive.run_steps.into_iter().map(Record::RunStep));
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(&record)?);
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// Reads an archive exported with `--format json` or `--format ndjson`, the
+/// first line of an ndjson archive is a record with a `type`.
+fn parse_archive(content: &str) -> Result<Archive, Error> {
+    let first = content.lines().find(|x| !x.trim().is_empty()).unwrap_or("");
+    let is_ndjson = matches!(
+        serde_json::from_str::<serde_json::Value>(first),
+        Ok(record) if record.get("type").is_some()
+    );
+
+    let archive = match is_ndjson {
+        true => parse_ndjson(content)?,
+        false => serde_json::from_str::<Archive>(content)
+            .map_err(|e| Error::msg(format!("Invalid archive: {}", e)))?,
+    };
+
+    if archive.version > ARCHIVE_VERSION {
+        return Err(Error::msg(format!(
+            "The archive was exported by a newer version of crosup (format {}, this one reads up to {}), please upgrade crosup to import it",
+            archive.version, ARCHIVE_VERSION
+        )));
+    }
+    Ok(archive)
+}
+
+fn parse_ndjson(content: &str) -> Result<Archive, Error> {
+    let mut archive = Archive::default();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str::<Record>(line)
+            .map_err(|e| Error::msg(format!("Invalid record at line {}: {}", index + 1, e)))?;
+        match record {
+            Record::Header { version } => archive.version = version,
+            Record::Project(project) => archive.projects.push(project),
+            Record::File(file) => archive.files.push(file),
+            Record::Modification(modification) => archive.modifications.push(modification),
+            Record::Run(run) => archive.runs.push(run),
+            Record::RunStep(step) => archive.run_steps.push(step),
+        }
+    }
+    Ok(archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn archive() -> Archive {
+        let timestamp = NaiveDate::from_ymd_opt(2026, 10, 19)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        Archive {
+            version: ARCHIVE_VERSION,
+            projects: vec![project::Model {
+                id: 1,
+                path: "/home/me".into(),
+                host: "laptop".into(),
+                created_at: timestamp,
+            }],
+            files: vec![file::Model {
+                id: 2,
+                name: "Crosfile.hcl".into(),
+                path: "/home/me/Crosfile.hcl".into(),
+                host: "laptop".into(),
+                created_at: timestamp,
+                project_id: Some(1),
+            }],
+            modifications: vec![modification::Model {
+                id: 3,
+                timestamp,
+                hash: "a".repeat(64),
+                file_id: 2,
+                previous_id: None,
+                content: "packages = [\"vim\"]\n".into(),
+                message: Some("first".into()),
+                tags: Some("stable".into()),
+            }],
+            runs: vec![run::Model {
+                id: 4,
+                file_id: 2,
+                modification_id: Some(3),
+                host: "localhost".into(),
+                status: "success".into(),
+                started_at: timestamp,
+                duration_ms: 1200,
+            }],
+            run_steps: vec![run_step::Model {
+                id: 5,
+                run_id: 4,
+                tool: "vim".into(),
+                provider: "nix".into(),
+                command: Some("nix profile install nixpkgs#vim".into()),
+                exit_status: 0,
+                duration_ms: 1000,
+                stdout: None,
+                stderr: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_archive() {
+        let json = serde_json::to_string_pretty(&archive()).unwrap();
+        assert_eq!(parse_archive(&json).unwrap(), archive());
+
+        let json = serde_json::to_string(&archive()).unwrap();
+        assert_eq!(parse_archive(&json).unwrap(), archive());
+
+        let ndjson = to_ndjson(archive()).unwrap();
+        assert!(ndjson.starts_with("{\"type\":\"header\",\"version\":1}\n"));
+        assert_eq!(parse_archive(&ndjson).unwrap(), archive());
+
+        // ndjson archives exported before the header was added
+        let ndjson = ndjson.lines().skip(1).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            parse_archive(&ndjson).unwrap(),
+            Archive {
+                version: 0,
+                ..archive()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_archive_newer_version() {
+        let newer = Archive {
+            version: ARCHIVE_VERSION + 1,
+            ..archive()
+        };
+        let json = serde_json::to_string_pretty(&newer).unwrap();
+        let error = parse_archive(&json).unwrap_err().to_string();
+        assert!(error.contains("newer version of crosup"), "{}", error);
+
+        let ndjson = to_ndjson(newer).unwrap();
+        let error = parse_archive(&ndjson).unwrap_err().to_string();
+        assert!(error.contains("newer version of crosup"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_archive_invalid() {
+        let json = serde_json::to_string_pretty(&archive()).unwrap();
+        let error = parse_archive(&json[..json.len() / 2])
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Invalid archive: "), "{}", error);
+
+        let json = json.replace("\"files\"", "\"file\"");
+        let error = parse_archive(&json).unwrap_err().to_string();
+        assert!(error.contains("missing field `files`"), "{}", error);
+
+        let ndjson = to_ndjson(archive())
+            .unwrap()
+            .replace("\"run_id\"", "\"run\"");
+        let error = parse_archive(&ndjson).unwrap_err().to_string();
+        assert!(error.starts_with("Invalid record at line 6: "), "{}", error);
+    }
+}
@@ -1,36 +1,89 @@
 use anyhow::Error;
-use crosup_core::config::verify_if_config_file_is_present;
-use crosup_repo::{file::FileRepo, modification::ModificationRepo};
-use migration::MigratorTrait;
+use crosup_core::{
+    config::{parse_configuration, verify_if_config_file_is_present},
+    diff::semantic_diff,
+};
+use crosup_repo::{database, file::FileRepo, modification::ModificationRepo};
 use owo_colors::OwoColorize;
 use sea_orm::DatabaseConnection;
 
-use crate::cmd::print_diff;
+use crate::cmd::{print_diff, resolve_revision};
 
 use super::get_database_connection;
 
-pub async fn execute_diff() -> Result<(), Error> {
+/// Compares the current configuration file with its last revision, with a
+/// given revision, or two revisions together.
+pub async fn execute_diff(
+    rev_a: Option<String>,
+    rev_b: Option<String>,
+    semantic: bool,
+) -> Result<(), Error> {
     let (_, filename, content, _) = verify_if_config_file_is_present()?;
 
     let db: DatabaseConnection = get_database_connection().await?;
-    migration::Migrator::up(&db, None).await?;
+
     let current_dir = std::env::current_dir()?;
     let path = format!("{}/{}", current_dir.display(), filename);
 
-    let result = FileRepo::new(&db).find_by_path(&path).await?;
-    if let Some(file) = result {
-        let result = ModificationRepo::new(&db)
-            .find_last_by_file_id(file.id)
-            .await?;
-        if let Some(last_modif) = result {
-            let hash = sha256::digest(content.clone());
-            if hash != last_modif.hash {
-                println!("   📝 {} has been modified", filename.bold().cyan());
-                print_diff(&last_modif.content, &content);
-                return Ok(());
+    let file = match FileRepo::new(&db)
+        .find_by_path(&database::host(), &path)
+        .await?
+    {
+        Some(file) => file,
+        None => {
+            println!(
+                "{} has not been modified, no history available",
+                filename.bold().cyan()
+            );
+            return Ok(());
+        }
+    };
+
+    let (previous, current) = match (rev_a, rev_b) {
+        (Some(a), Some(b)) => (
+            resolve_revision(&db, file.id, &a).await?.content,
+            resolve_revision(&db, file.id, &b).await?.content,
+        ),
+        (Some(a), None) => (resolve_revision(&db, file.id, &a).await?.content, content),
+        (None, _) => {
+            let last_modif = ModificationRepo::new(&db)
+                .find_last_by_file_id(file.id)
+                .await?;
+            match last_modif {
+                Some(last_modif) => (last_modif.content, content),
+                None => return Ok(()),
             }
-            println!("{} has not been modified", filename.bold().cyan());
         }
+    };
+
+    if previous == current {
+        println!("{} has not been modified", filename.bold().cyan());
+        return Ok(());
+    }
+
+    println!("   📝 {} has been modified", filename.bold().cyan());
+
+    if !semantic {
+        print_diff(&previous, &current);
+        return Ok(());
     }
+
+    let changes = semantic_diff(
+        &parse_configuration(&filename, &previous)?,
+        &parse_configuration(&filename, &current)?,
+    )?;
+    for change in changes {
+        println!("{}", change.block.bold().cyan());
+        for added in change.added {
+            println!("  {} {}", "+".green().bold(), added.green());
+        }
+        for removed in change.removed {
+            println!("  {} {}", "-".red().bold(), removed.red());
+        }
+        for changed in change.changed {
+            println!("  {} {}", "~".yellow().bold(), changed.yellow());
+        }
+    }
+
     Ok(())
 }
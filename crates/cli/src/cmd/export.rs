@@ -0,0 +1,45 @@
+use std::io::Write;
+
+use anyhow::Error;
+use crosup_core::{brewfile, config::verify_if_config_file_is_present};
+use inquire::Confirm;
+use owo_colors::OwoColorize;
+
+use crate::types::ExportArgs;
+
+pub fn execute_export(args: ExportArgs) -> Result<(), Error> {
+    let (config, filename, _, _) = verify_if_config_file_is_present()?;
+
+    let brew = match config.brew {
+        Some(brew) => brew,
+        None => {
+            println!("-> No brew block found in {}", filename.bright_green());
+            return Ok(());
+        }
+    };
+
+    let output = args.output.unwrap_or("Brewfile".into());
+
+    if std::path::Path::new(&output).exists() {
+        let answer = Confirm::new(
+            format!(
+                "A {} file already exists in this directory, do you want to overwrite it?",
+                output.bright_green()
+            )
+            .as_str(),
+        )
+        .with_default(false)
+        .with_help_message("Press y to overwrite the file or n to exit")
+        .prompt();
+        if answer.is_err() || !answer.unwrap() {
+            println!("Exiting...");
+            return Ok(());
+        }
+    }
+
+    let mut file = std::fs::File::create(&output)?;
+    file.write_all(brewfile::render(&brew).as_bytes())?;
+    println!("Created {} ✨", output.bright_green());
+
+    Ok(())
+}
@@ -1,48 +1,100 @@
 use std::collections::HashMap;
 
-use super::get_database_connection;
+use super::{get_database_connection, resolve_revision, rollback::restore_revision, validate_tag};
 use anyhow::Error;
 use crosup_core::config::verify_if_config_file_is_present;
-use crosup_repo::{file::FileRepo, modification::ModificationRepo};
-use crosup_tui::{history::display_history, App};
-use migration::MigratorTrait;
+use crosup_entity::{file, modification, run, run_step};
+use crosup_repo::{
+    database,
+    file::FileRepo,
+    modification::{tags, ModificationRepo},
+    project::ProjectRepo,
+    run::RunRepo,
+    run_step::RunStepRepo,
+};
+use crosup_tui::{history::display_history, Action, App};
 use owo_colors::OwoColorize;
 use sea_orm::DatabaseConnection;
 
-pub async fn execute_history() -> Result<(), Error> {
-    let (_, filename, _, _) = verify_if_config_file_is_present()?;
+pub async fn execute_history(runs: bool, file: Option<String>, plain: bool) -> Result<(), Error> {
+    let current_dir = std::env::current_dir()?;
+    // only the Crosfile can be restored with `crosup rollback`
+    let (filename, is_crosfile) = match file {
+        Some(file) => {
+            let is_crosfile = matches!(
+                verify_if_config_file_is_present(),
+                Ok((_, crosfile, _, _)) if current_dir.join(&file) == current_dir.join(&crosfile)
+            );
+            (file, is_crosfile)
+        }
+        None => (verify_if_config_file_is_present()?.1, true),
+    };
 
     let db: DatabaseConnection = get_database_connection().await?;
 
-    migration::Migrator::up(&db, None).await?;
-
-    let current_dir = std::env::current_dir()?;
-    let path = format!("{}/{}", current_dir.display(), filename);
+    let path = current_dir.join(&filename).display().to_string();
 
-    let result = FileRepo::new(&db).find_by_path(&path).await?;
+    let result = FileRepo::new(&db)
+        .find_by_path(&database::host(), &path)
+        .await?;
 
     if let Some(file) = result {
-        let result = ModificationRepo::new(&db).find_by_file_id(file.id).await?;
-        let mut content = HashMap::new();
-        let mut dates = HashMap::new();
-        let mut hashes = HashMap::new();
-        for (index, m) in result.iter().enumerate() {
-            content.insert(index, m.content.clone());
-            dates.insert(index, m.timestamp);
-            hashes.insert(index, m.hash.clone());
+        if runs {
+            return display_runs(&db, &file, filename, plain, is_crosfile).await;
         }
 
-        let app = App {
-            items: result
-                .into_iter()
-                .map(|m| format!("{} {} {}", m.timestamp.to_string(), filename, m.hash))
-                .collect(),
-            content,
-            selected_index: 0,
-            title: filename,
-        };
-        display_history(app)?;
-        return Ok(());
+        let mut status = None;
+        loop {
+            let result = ModificationRepo::new(&db).find_by_file_id(file.id).await?;
+
+            if plain {
+                for (index, m) in result.iter().enumerate() {
+                    let mut line = format!(
+                        "{:>3}  {}  {}",
+                        index,
+                        m.timestamp,
+                        m.hash.get(..8).unwrap_or(&m.hash).bright_green()
+                    );
+                    if !tags(m).is_empty() {
+                        line.push_str(&format!("  ({})", tags(m).join(", ").bold().cyan()));
+                    }
+                    if let Some(message) = &m.message {
+                        line.push_str(&format!("  {}", message));
+                    }
+                    println!("{}", line);
+                }
+                return Ok(());
+            }
+
+            let mut content = HashMap::new();
+            let mut hashes = HashMap::new();
+            for (index, m) in result.iter().enumerate() {
+                content.insert(index, m.content.clone());
+                hashes.insert(index, m.hash.clone());
+            }
+
+            let app = App {
+                items: result
+                    .iter()
+                    .map(|m| format_revision(m, &filename))
+                    .collect(),
+                content,
+                hashes,
+                selected_index: 0,
+                current: std::fs::read_to_string(&path).ok(),
+                targets: HashMap::new(),
+                title: filename.clone(),
+                revisions: true,
+                status: status.take(),
+            };
+            status = match display_history(app)? {
+                Action::Rollback(index) if is_crosfile => {
+                    Some(rollback_from_tui(&db, &file, &filename, &result[index]).await?)
+                }
+                Action::Rollback(_) => Some("Only the Crosfile can be rolled back".into()),
+                Action::None => return Ok(()),
+            };
+        }
     }
 
     println!(
@@ -50,5 +102,219 @@ pub async fn execute_history() -> Result<(), Error> {
         filename.bold().cyan()
     );
 
+    let directory = current_dir.display().to_string();
+    if let Some(project) = ProjectRepo::new(&db)
+        .find_by_path(&database::host(), &directory)
+        .await?
+    {
+        let files = FileRepo::new(&db).find_by_project_id(project.id).await?;
+        let names: Vec<String> = files.into_iter().map(|f| f.name).collect();
+        if !names.is_empty() {
+            println!(
+                "-> Tracked files in this directory: {}, use {} to browse them",
+                names.join(", ").bright_green(),
+                "crosup history --file <file>".bright_green()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Line of a revision in the history view, e.g.
+/// `2023-06-30 12:00:00 Crosfile.hcl 8d9c... (stable) add k8s tools`.
+fn format_revision(m: &modification::Model, filename: &str) -> String {
+    let mut line = format!("{} {} {}", m.timestamp, filename, m.hash);
+    if !tags(m).is_empty() {
+        line.push_str(&format!(" ({})", tags(m).join(", ")));
+    }
+    if let Some(message) = &m.message {
+        line.push_str(&format!(" {}", message));
+    }
+    line
+}
+
+/// Restores a revision picked in the history view, the view is then
+/// reopened with the returned message in its status bar.
+async fn rollback_from_tui(
+    db: &DatabaseConnection,
+    file: &file::Model,
+    filename: &str,
+    target: &modification::Model,
+) -> Result<String, Error> {
+    let short = target.hash.get(..8).unwrap_or(&target.hash);
+    let current = std::fs::read_to_string(&file.path).unwrap_or_default();
+    if current == target.content {
+        return Ok(format!("{} is already at revision {}", filename, short));
+    }
+    restore_revision(db, file.id, &file.path, target).await?;
+    Ok(format!("Restored {} to revision {} ✨", filename, short))
+}
+
+pub async fn execute_history_tag(
+    file: Option<String>,
+    revision: &str,
+    tag: &str,
+    delete: bool,
+) -> Result<(), Error> {
+    let filename = match file {
+        Some(file) => file,
+        None => verify_if_config_file_is_present()?.1,
+    };
+
+    let db: DatabaseConnection = get_database_connection().await?;
+
+    let path = std::env::current_dir()?
+        .join(&filename)
+        .display()
+        .to_string();
+    let file = FileRepo::new(&db)
+        .find_by_path(&database::host(), &path)
+        .await?
+        .ok_or(Error::msg(format!(
+            "{} has not been modified, no history available",
+            filename
+        )))?;
+    let modification = resolve_revision(&db, file.id, revision).await?;
+    let hash = modification.hash.clone();
+
+    if delete {
+        ModificationRepo::new(&db)
+            .remove_tag(modification, tag)
+            .await?;
+        println!(
+            "Removed tag {} from revision {} ✨",
+            tag.bright_green(),
+            hash.bright_green()
+        );
+        return Ok(());
+    }
+
+    validate_tag(tag)?;
+    ModificationRepo::new(&db)
+        .add_tag(modification, tag)
+        .await?;
+    println!(
+        "Tagged revision {} as {} ✨",
+        hash.bright_green(),
+        tag.bright_green()
+    );
     Ok(())
 }
+
+async fn display_runs(
+    db: &DatabaseConnection,
+    file: &file::Model,
+    filename: String,
+    plain: bool,
+    is_crosfile: bool,
+) -> Result<(), Error> {
+    let runs = RunRepo::new(db).find_by_file_id(file.id).await?;
+
+    if runs.is_empty() {
+        println!(
+            "{} has not been installed yet, no runs available",
+            filename.bold().cyan()
+        );
+        return Ok(());
+    }
+
+    let mut items = vec![];
+    let mut content = HashMap::new();
+    let mut hashes = HashMap::new();
+    let mut targets = HashMap::new();
+    for (index, run) in runs.iter().enumerate() {
+        let steps = RunStepRepo::new(db).find_by_run_id(run.id).await?;
+        let modification = match run.modification_id {
+            Some(id) => ModificationRepo::new(db).find(id).await?,
+            None => None,
+        };
+        let hash = modification.as_ref().map(|m| m.hash.clone());
+        if let Some(modification) = modification {
+            targets.insert(index, modification.content);
+        }
+        items.push(format!(
+            "{} {} {} {:.1}s ({} steps)",
+            run.started_at,
+            run.host,
+            run.status,
+            run.duration_ms as f64 / 1000.0,
+            steps.len()
+        ));
+        content.insert(index, format_run(run, &steps, hash.clone()));
+        if let Some(hash) = hash {
+            hashes.insert(index, hash);
+        }
+    }
+
+    if plain {
+        for (index, item) in items.iter().enumerate() {
+            println!("{:>3}  {}", index, item);
+        }
+        return Ok(());
+    }
+
+    let mut status = None;
+    loop {
+        let app = App {
+            items: items.clone(),
+            content: content.clone(),
+            hashes: hashes.clone(),
+            selected_index: 0,
+            current: std::fs::read_to_string(&file.path).ok(),
+            targets: targets.clone(),
+            title: format!("{} runs", filename),
+            revisions: false,
+            status: status.take(),
+        };
+        // rolling back from a run restores the configuration it installed
+        status = match display_history(app)? {
+            Action::Rollback(_) if !is_crosfile => {
+                Some("Only the Crosfile can be rolled back".into())
+            }
+            Action::Rollback(index) => {
+                let target = ModificationRepo::new(db)
+                    .find_by_hash(file.id, &hashes[&index])
+                    .await?
+                    .ok_or(Error::msg("The configuration of this run has been pruned"))?;
+                Some(rollback_from_tui(db, file, &filename, &target).await?)
+            }
+            Action::None => return Ok(()),
+        };
+    }
+}
+
+fn format_run(run: &run::Model, steps: &[run_step::Model], hash: Option<String>) -> String {
+    let mut lines = vec![
+        format!(
+            "Run #{} on {} ({}), started at {}, took {:.1}s",
+            run.id,
+            run.host,
+            run.status,
+            run.started_at,
+            run.duration_ms as f64 / 1000.0
+        ),
+        format!("Configuration: {}", hash.unwrap_or("unknown".into())),
+        String::new(),
+    ];
+    for step in steps {
+        let status = match step.exit_status {
+            0 => "ok",
+            _ => "failed",
+        };
+        lines.push(format!(
+            "[{}] {} ({}) {:.1}s",
+            status,
+            step.tool,
+            step.provider,
+            step.duration_ms as f64 / 1000.0
+        ));
+        if let Some(command) = &step.command {
+            lines.extend(command.lines().map(|x| format!("  $ {}", x)));
+        }
+        for output in [&step.stdout, &step.stderr].into_iter().flatten() {
+            lines.extend(output.lines().map(|x| format!("  {}", x)));
+        }
+    }
+    lines.join("\n")
+}
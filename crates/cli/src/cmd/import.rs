@@ -0,0 +1,75 @@
+use std::io::Write;
+
+use anyhow::Error;
+use crosup_core::import::{import_from_brewfile, import_from_system};
+use crosup_ssh::setup_ssh_connection;
+use crosup_types::configuration::ConfigFormat;
+use inquire::Confirm;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+
+use crate::types::ImportArgs;
+
+pub fn execute_import(args: ImportArgs) -> Result<(), Error> {
+    if !args.from_system && args.brewfile.is_none() {
+        return Err(Error::msg(
+            "Nothing to import, use --from-system to import the packages installed on this machine or the brewfile subcommand",
+        ));
+    }
+
+    let cfg_format = match args.toml {
+        true => ConfigFormat::TOML,
+        false => ConfigFormat::HCL,
+    };
+    let filename = match (args.output.clone(), &cfg_format) {
+        (Some(output), _) => output,
+        (None, ConfigFormat::HCL) => "Crosfile.hcl".into(),
+        (None, ConfigFormat::TOML) => "Crosfile.toml".into(),
+    };
+
+    if std::path::Path::new(&filename).exists() {
+        let answer = Confirm::new(
+            format!(
+                "A {} file already exists in this directory, do you want to overwrite it?",
+                filename.bright_green()
+            )
+            .as_str(),
+        )
+        .with_default(false)
+        .with_help_message("Press y to overwrite the file or n to exit")
+        .prompt();
+        if answer.is_err() || !answer.unwrap() {
+            println!("Exiting...");
+            return Ok(());
+        }
+    }
+
+    let config = match args.brewfile.as_ref() {
+        Some(path) => import_from_brewfile(&std::fs::read_to_string(path)?)?,
+        None => import_from_system(parse_args(&args)?)?,
+    };
+
+    let serialized = match cfg_format {
+        ConfigFormat::HCL => hcl::to_string(&config)?,
+        ConfigFormat::TOML => toml::to_string_pretty(&config)?,
+    };
+
+    let mut file = std::fs::File::create(&filename)?;
+    file.write_all(serialized.as_bytes())?;
+    println!("Created {} ✨", filename.bright_green());
+
+    Ok(())
+}
+
+fn parse_args(args: &ImportArgs) -> Result<Option<Session>, Error> {
+    match args.remote.as_ref() {
+        Some(remote) => {
+            let username = args.username.as_ref().ok_or(Error::msg(
+                "username is required, please use -u or --username",
+            ))?;
+            let addr = format!("{}:{}", remote, args.port.unwrap_or(22));
+            Ok(Some(setup_ssh_connection(&addr, username)?))
+        }
+        None => Ok(None),
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::Error;
 use inquire::Confirm;
 use owo_colors::OwoColorize;
 
+use crosup_core::config::default_configuration;
 use crosup_types::{
     configuration::{ConfigFormat, Configuration},
     inventory::Inventory,
@@ -58,6 +59,7 @@ pub fn execute_init(
         Some(packages) => Configuration {
             packages: Some(packages),
             install: None,
+            distro: None,
             brew: None,
             apt: None,
             pacman: None,
@@ -70,9 +72,21 @@ pub fn execute_init(
             apk: None,
             emerge: None,
             slackpkg: None,
+            xbps: None,
+            rpm_ostree: None,
             fleek: None,
+            cargo: None,
+            go: None,
+            pipx: None,
+            npm: None,
+            release: None,
+            archive: None,
+            runtime: None,
+            file: None,
+            service: None,
+            flatpak: None,
         },
-        None => Configuration::default(),
+        None => default_configuration(),
     };
 
     let serialized = match cfg_format {
@@ -1,27 +1,47 @@
-use std::fs;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    os::fd::AsRawFd,
+    time::{Duration, Instant},
+};
 
 use anyhow::Error;
 use crosup_core::{
-    config::{verify_if_config_file_is_present, verify_if_inventory_config_file_is_present},
-    graph::build_installer_graph,
+    config::{
+        default_configuration, verify_if_config_file_is_present,
+        verify_if_inventory_config_file_is_present,
+    },
+    graph::{build_installer_graph, Step},
 };
-use crosup_repo::{file::FileRepo, modification::ModificationRepo};
+use crosup_entity::run_step;
+use crosup_repo::{run::RunRepo, run_step::RunStepRepo};
 use crosup_ssh::setup_ssh_connection;
+use crosup_tui::picker::Tool;
 use crosup_types::configuration::Configuration;
-use migration::MigratorTrait;
 use owo_colors::OwoColorize;
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::DatabaseConnection;
 use ssh2::Session;
 
+use super::{get_database_connection, record_revision};
 use crate::{macros::install, types::InstallArgs};
 
-pub async fn execute_install(args: InstallArgs) -> Result<(), Error> {
+/// Installs the tools of the configuration and records the run, returns the
+/// tools which were installed or already installed.
+pub async fn execute_install(mut args: InstallArgs) -> Result<Vec<String>, Error> {
+    if args.interactive && args.tools.is_some() {
+        return Err(Error::msg(format!(
+            "{} picks the tools from the configuration, it can't be used with a list of tools",
+            "--interactive".bright_green()
+        )));
+    }
+
     let (config, filename, content, _) = verify_if_config_file_is_present()?;
 
     let mut config = match args.tools.clone() {
         Some(packages) => Configuration {
             packages: Some(packages),
             install: None,
+            distro: config.distro.clone(),
             brew: None,
             apt: None,
             pacman: None,
@@ -34,74 +54,156 @@ pub async fn execute_install(args: InstallArgs) -> Result<(), Error> {
             apk: None,
             emerge: None,
             slackpkg: None,
+            xbps: None,
+            rpm_ostree: None,
             fleek: None,
+            cargo: None,
+            go: None,
+            pipx: None,
+            npm: None,
+            release: None,
+            archive: None,
+            runtime: None,
+            file: None,
+            service: None,
+            flatpak: None,
         },
         None => config,
     };
 
-    ask_confirmation(args.ask, &mut config);
+    if let Some(package_manager) = args.package_manager.clone() {
+        match config.install.as_mut() {
+            Some(install) => install.package_manager = Some(package_manager),
+            None => println!(
+                "-> No install block found, ignoring {}",
+                "--package-manager".bright_green()
+            ),
+        }
+    }
+
+    match args.interactive {
+        true => args.selected = Some(pick_tools(&args, &mut config, &filename)?),
+        false => ask_confirmation(args.ask, &mut config)?,
+    }
 
-    let mut sessions = Vec::new();
+    let mut hosts = Vec::new();
+    let mut inventory = None;
 
     if args.remote_is_present {
-        sessions = parse_args(&args)?;
+        (hosts, inventory) = parse_args(&args)?;
     }
 
-    match sessions.len() {
-        0 => {
-            install!(args, config, None);
-        }
+    let runs: Vec<InstallRun> = match hosts.len() {
+        0 => vec![run_install(&args, config, "localhost".into(), None)],
         _ => {
             println!(
                 "-> Installing tools on {} machine{}",
-                sessions.len().bold().cyan(),
-                if sessions.len() > 1 { "s" } else { "" }
+                hosts.len().bold().cyan(),
+                if hosts.len() > 1 { "s" } else { "" }
             );
             let mut children = Vec::new();
-            for session in sessions {
+            for (host, session) in hosts {
                 let args = args.clone();
-                let mut config = config.clone();
+                let config = config.clone();
 
-                let child = std::thread::spawn(move || {
-                    install!(args, config, Some(session.clone()));
-                    Ok::<(), Error>(())
-                });
+                let child =
+                    std::thread::spawn(move || run_install(&args, config, host, Some(session)));
                 children.push(child);
             }
 
-            for child in children {
-                child.join().unwrap()?;
-            }
+            children
+                .into_iter()
+                .map(|child| child.join().unwrap())
+                .collect()
         }
-    }
-
-    let home = std::env::var("HOME").unwrap();
-    let crosup_dir = format!("{}/.config/crosup", home);
+    };
 
-    fs::create_dir_all(&crosup_dir)?;
+    let db: DatabaseConnection = get_database_connection().await?;
 
-    let database_url = format!("sqlite:{}/modifications.sqlite3?mode=rwc", crosup_dir);
+    let message = args.message.as_deref();
+    let (file, modification) =
+        record_revision(&db, &filename, &content, message, &args.tags).await?;
 
-    let db: DatabaseConnection = Database::connect(&database_url).await?;
+    if let Some((filename, content)) = inventory {
+        record_revision(&db, &filename, &content, message, &[]).await?;
+    }
 
-    migration::Migrator::up(&db, None).await?;
+    for run in runs.iter() {
+        let status = match run.result {
+            Ok(_) => "success",
+            Err(_) => "failed",
+        };
+        let model = RunRepo::new(&db)
+            .create(
+                file.id,
+                Some(modification.id),
+                &run.host,
+                status,
+                run.started_at,
+                run.duration.as_millis() as i64,
+            )
+            .await?;
+        for step in run.steps.iter() {
+            RunStepRepo::new(&db)
+                .create(run_step::Model {
+                    id: 0,
+                    run_id: model.id,
+                    tool: step.tool.clone(),
+                    provider: step.provider.clone(),
+                    command: step.command.clone(),
+                    exit_status: step.exit_status,
+                    duration_ms: step.duration.as_millis() as i64,
+                    stdout: step.stdout.clone(),
+                    stderr: step.stderr.clone(),
+                })
+                .await?;
+        }
+    }
 
-    let current_dir = std::env::current_dir()?;
-    let path = format!("{}/{}", current_dir.display(), filename);
+    let mut installed = vec![];
+    for run in runs {
+        run.result?;
+        installed.extend(run.steps.into_iter().map(|step| step.tool));
+    }
 
-    let file = FileRepo::new(&db).create(&filename, &path).await?;
+    Ok(installed)
+}
 
-    let hash = sha256::digest(content.clone());
-    ModificationRepo::new(&db)
-        .create(file.id, &hash, &content)
-        .await?;
+/// Outcome of an install on a single machine, recorded in the history
+/// database whether it succeeded or not.
+struct InstallRun {
+    host: String,
+    started_at: chrono::NaiveDateTime,
+    duration: Duration,
+    steps: Vec<Step>,
+    result: Result<(), Error>,
+}
 
-    Ok(())
+fn run_install(
+    args: &InstallArgs,
+    mut config: Configuration,
+    host: String,
+    session: Option<Session>,
+) -> InstallRun {
+    let started_at = chrono::Utc::now().naive_utc();
+    let start = Instant::now();
+    let mut steps = vec![];
+    let result = (|| {
+        install!(args, config, session, steps);
+        Ok::<(), Error>(())
+    })();
+    InstallRun {
+        host,
+        started_at,
+        duration: start.elapsed(),
+        steps,
+        result,
+    }
 }
 
-fn ask_confirmation(ask: bool, config: &mut Configuration) {
+fn ask_confirmation(ask: bool, config: &mut Configuration) -> Result<(), Error> {
     if ask {
-        let (_, installers) = build_installer_graph(config, None);
+        let (_, installers) = build_installer_graph(config, None)?;
         println!("-> The following tools will be installed:");
 
         for installer in installers.iter() {
@@ -128,9 +230,78 @@ fn ask_confirmation(ask: bool, config: &mut Configuration) {
             _ => std::process::exit(0),
         }
     }
+    Ok(())
+}
+
+/// Opens a screen listing the tools of the configuration to pick the ones to
+/// install, their dependencies are installed too.
+fn pick_tools(
+    args: &InstallArgs,
+    config: &mut Configuration,
+    filename: &str,
+) -> Result<Vec<String>, Error> {
+    let (_, installers) = build_installer_graph(config, None)?;
+    if !args.remote_is_present {
+        println!("-> Checking which tools are already installed");
+    }
+    let tools: Vec<Tool> = installers
+        .iter()
+        .map(|installer| Tool {
+            name: installer.name().to_string(),
+            provider: installer.provider().to_string(),
+            dependencies: installer.dependencies(),
+            // the status is only known for the local machine
+            installed: match args.remote_is_present {
+                true => None,
+                false => Some(quietly(|| installer.is_installed().unwrap_or(false))),
+            },
+        })
+        .collect();
+
+    match crosup_tui::picker::pick_tools(filename, &tools)? {
+        Some(selected) if !selected.is_empty() => Ok(selected),
+        Some(_) => {
+            println!("-> No tools selected, exiting...");
+            std::process::exit(0)
+        }
+        None => {
+            println!("Exiting...");
+            std::process::exit(0)
+        }
+    }
 }
 
-fn parse_args(args: &InstallArgs) -> Result<Vec<Session>, Error> {
+/// Runs `f` with the standard output and error sent to /dev/null, checking
+/// whether a tool is installed prints what is checked and runs commands
+/// such as `npm ls` whose output would end up under the picker.
+fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let null = match OpenOptions::new().write(true).open("/dev/null") {
+        Ok(null) => null,
+        Err(_) => return f(),
+    };
+    io::stdout().flush().ok();
+    let (stdout, stderr) = unsafe { (libc::dup(1), libc::dup(2)) };
+    unsafe {
+        libc::dup2(null.as_raw_fd(), 1);
+        libc::dup2(null.as_raw_fd(), 2);
+    }
+    let result = f();
+    io::stdout().flush().ok();
+    unsafe {
+        libc::dup2(stdout, 1);
+        libc::dup2(stderr, 2);
+        libc::close(stdout);
+        libc::close(stderr);
+    }
+    result
+}
+
+/// Address of each remote machine with its ssh session.
+type Hosts = Vec<(String, Session)>;
+
+/// Connects to the remote machine given with `--remote`, or to the servers
+/// of the inventory, whose filename and content are returned to be recorded.
+fn parse_args(args: &InstallArgs) -> Result<(Hosts, Option<(String, String)>), Error> {
     let remote = args.remote.as_ref();
 
     match remote {
@@ -144,18 +315,19 @@ fn parse_args(args: &InstallArgs) -> Result<Vec<Session>, Error> {
             let username = args.username.as_ref().unwrap();
             let addr = format!("{}:{}", *remote, port);
             let session = setup_ssh_connection(&addr, username)?;
-            Ok(vec![session])
+            Ok((vec![(addr, session)], None))
         }
         None => {
-            let config = verify_if_inventory_config_file_is_present()?;
+            let (config, filename, content) =
+                verify_if_inventory_config_file_is_present(args.inventory.as_deref())?;
             let mut sessions = Vec::new();
             for (_, server) in config.server.iter() {
                 let port = server.port.unwrap_or(22);
                 let addr = format!("{}:{}", server.host, port);
                 let session = setup_ssh_connection(&addr, &server.username).unwrap();
-                sessions.push(session);
+                sessions.push((addr, session));
             }
-            return Ok(sessions);
+            return Ok((sessions, Some((filename, content))));
         }
     }
 }
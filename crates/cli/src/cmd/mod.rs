@@ -1,16 +1,31 @@
-use std::{fmt, fs};
+use std::{fmt, path::Path};
 
 use anyhow::Error;
+use chrono::{NaiveDate, NaiveDateTime};
+use crosup_entity::{file, modification};
+use crosup_repo::{
+    database,
+    file::FileRepo,
+    modification::{tags, ModificationRepo},
+    project::ProjectRepo,
+};
+use migration::MigratorTrait;
 use owo_colors::{OwoColorize, Style};
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::DatabaseConnection;
 use similar::{ChangeTag, TextDiff};
 
 pub mod add;
+pub mod archive;
 pub mod diff;
+pub mod export;
 pub mod history;
+pub mod import;
 pub mod init;
 pub mod install;
+pub mod prune;
+pub mod rollback;
 pub mod search;
+pub mod uninstall;
 
 struct Line(Option<usize>);
 
@@ -23,16 +38,147 @@ impl fmt::Display for Line {
     }
 }
 
+/// Connects to the history database (see `crosup_repo::database`) and
+/// applies the pending migrations.
 pub async fn get_database_connection() -> Result<DatabaseConnection, Error> {
-    let home = std::env::var("HOME").unwrap();
-    let crosup_dir = format!("{}/.config/crosup", home);
+    let db: DatabaseConnection = database::connect().await?;
+    migration::Migrator::up(&db, None).await?;
+    Ok(db)
+}
 
-    fs::create_dir_all(&crosup_dir)?;
+/// Records the content of a tracked file (Crosfile, Inventory) as a new
+/// revision with an optional message and tags, the file is attached to the
+/// project of its directory.
+pub async fn record_revision(
+    db: &DatabaseConnection,
+    filename: &str,
+    content: &str,
+    message: Option<&str>,
+    tags: &[String],
+) -> Result<(file::Model, modification::Model), Error> {
+    for tag in tags {
+        validate_tag(tag)?;
+    }
 
-    let database_url = format!("sqlite:{}/modifications.sqlite3?mode=rwc", crosup_dir);
+    let path = std::env::current_dir()?.join(filename);
+    let directory = path.parent().unwrap_or(Path::new("/"));
+    let name = path
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or(filename.into());
 
-    let db: DatabaseConnection = Database::connect(&database_url).await?;
-    Ok(db)
+    let host = database::host();
+    let project = ProjectRepo::new(db)
+        .create(&host, &directory.display().to_string())
+        .await?;
+    let file = FileRepo::new(db)
+        .create(&host, &name, &path.display().to_string(), project.id)
+        .await?;
+
+    let hash = sha256::digest(content.to_string());
+    let mut modification = ModificationRepo::new(db)
+        .create(file.id, &hash, content, message)
+        .await?;
+    for tag in tags {
+        modification = ModificationRepo::new(db).add_tag(modification, tag).await?;
+    }
+    Ok((file, modification))
+}
+
+/// Tags can't be mistaken for an index, a hash prefix or a date when
+/// resolving a revision.
+pub fn validate_tag(tag: &str) -> Result<(), Error> {
+    if tag.is_empty()
+        || tag.contains(|c: char| c == ',' || c.is_whitespace())
+        || tag.chars().all(|c| c.is_ascii_digit())
+        || parse_date(tag).is_some()
+    {
+        return Err(Error::msg(format!(
+            "Invalid tag {}, tags can't be a number or a date nor contain commas or spaces",
+            tag
+        )));
+    }
+    Ok(())
+}
+
+/// Parses `2023-06-30` (end of the day), `2023-06-30 12:00` or
+/// `2023-06-30 12:00:00`, in UTC like the history timestamps.
+fn parse_date(revision: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(revision, "%Y-%m-%d") {
+        return date.and_hms_opt(23, 59, 59);
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(revision, format).ok())
+}
+
+/// Parses a duration like `90d`, `12w`, `24h`, `30m` or `45s`, negative or
+/// out of range durations are rejected.
+fn parse_duration(duration: &str) -> Option<chrono::Duration> {
+    let unit = duration.chars().last()?;
+    let value = duration[..duration.len() - unit.len_utf8()]
+        .parse::<i64>()
+        .ok()
+        .filter(|x| *x >= 0)?;
+    match unit {
+        's' => chrono::Duration::try_seconds(value),
+        'm' => chrono::Duration::try_minutes(value),
+        'h' => chrono::Duration::try_hours(value),
+        'd' => chrono::Duration::try_days(value),
+        'w' => chrono::Duration::try_weeks(value),
+        _ => None,
+    }
+}
+
+/// Finds a revision of a file from its index in `crosup history` (0 being the
+/// latest one), a tag, a prefix of at least 4 characters of its hash, or a
+/// date, in which case the last revision at that date is returned. A number
+/// is an index when there is a revision at that index, a hash prefix
+/// otherwise.
+pub async fn resolve_revision(
+    db: &DatabaseConnection,
+    file_id: i32,
+    revision: &str,
+) -> Result<modification::Model, Error> {
+    let modifications = ModificationRepo::new(db).find_by_file_id(file_id).await?;
+    let index = revision.parse::<usize>().ok();
+
+    if let Some(modification) = index.and_then(|index| modifications.get(index)) {
+        return Ok(modification.clone());
+    }
+
+    if let Some(date) = parse_date(revision) {
+        return modifications
+            .into_iter()
+            .find(|m| m.timestamp <= date)
+            .ok_or(Error::msg(format!("No revision before {}", revision)));
+    }
+
+    if let Some(index) = modifications
+        .iter()
+        .position(|m| tags(m).contains(&revision))
+    {
+        return Ok(modifications.into_iter().nth(index).unwrap());
+    }
+
+    if revision.len() < 4 {
+        return Err(match index {
+            Some(index) => Error::msg(format!("No revision at index {}", index)),
+            None => Error::msg(format!(
+                "Invalid revision {}, expected an index or a hash of at least 4 characters",
+                revision
+            )),
+        });
+    }
+
+    let mut matches = modifications
+        .into_iter()
+        .filter(|m| m.hash.starts_with(revision));
+    match (matches.next(), matches.next()) {
+        (Some(m), None) => Ok(m),
+        (Some(_), Some(_)) => Err(Error::msg(format!("Ambiguous revision {}", revision))),
+        (None, _) => Err(Error::msg(format!("No revision matching {}", revision))),
+    }
 }
 
 pub fn print_diff(previous: &str, current: &str) {
@@ -64,3 +210,153 @@ pub fn print_diff(previous: &str, current: &str) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crosup_repo::{file::FileRepo, project::ProjectRepo};
+    use sea_orm::Database;
+
+    use super::*;
+
+    /// A file with four revisions, from the latest to the oldest:
+    /// `bbbb3333` (tagged v2), `aaaa2222`, `aaaa1111` (tagged stable) and
+    /// `12345678`, recorded on the 3rd, 2nd and 1st of January and on the
+    /// 30th of December.
+    async fn history() -> Result<(DatabaseConnection, i32), Error> {
+        let db = Database::connect("sqlite::memory:").await?;
+        migration::Migrator::up(&db, None).await?;
+        let project = ProjectRepo::new(&db)
+            .create("localhost", "/home/me")
+            .await?;
+        let file = FileRepo::new(&db)
+            .create(
+                "localhost",
+                "Crosfile.hcl",
+                "/home/me/Crosfile.hcl",
+                project.id,
+            )
+            .await?;
+        for (date, hash, tags) in [
+            ((2025, 12, 30), "12345678", None),
+            ((2026, 1, 1), "aaaa1111", Some("stable")),
+            ((2026, 1, 2), "aaaa2222", None),
+            ((2026, 1, 3), "bbbb3333", Some("v2")),
+        ] {
+            let (year, month, day) = date;
+            ModificationRepo::new(&db)
+                .insert(modification::Model {
+                    id: 0,
+                    timestamp: NaiveDate::from_ymd_opt(year, month, day)
+                        .unwrap()
+                        .and_hms_opt(10, 0, 0)
+                        .unwrap(),
+                    hash: hash.repeat(8),
+                    file_id: file.id,
+                    previous_id: None,
+                    content: hash.into(),
+                    message: None,
+                    tags: tags.map(|x| x.into()),
+                })
+                .await?;
+        }
+        Ok((db, file.id))
+    }
+
+    async fn resolve(db: &DatabaseConnection, file_id: i32, revision: &str) -> String {
+        match resolve_revision(db, file_id, revision).await {
+            Ok(modification) => modification.content,
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_revision() -> Result<(), Error> {
+        let (db, file_id) = history().await?;
+
+        for (revision, expected) in [
+            // index in `crosup history`
+            ("0", "bbbb3333"),
+            ("2", "aaaa1111"),
+            ("3", "12345678"),
+            ("4", "No revision at index 4"),
+            (
+                "ab",
+                "Invalid revision ab, expected an index or a hash of at least 4 characters",
+            ),
+            // tag
+            ("stable", "aaaa1111"),
+            ("v2", "bbbb3333"),
+            // hash prefix
+            ("bbbb", "bbbb3333"),
+            ("aaaa2", "aaaa2222"),
+            ("aaaa", "Ambiguous revision aaaa"),
+            ("cccc", "No revision matching cccc"),
+            // a number without a revision at that index
+            ("1234", "12345678"),
+            ("9999", "No revision matching 9999"),
+            // last revision at that date
+            ("2026-01-02", "aaaa2222"),
+            ("2026-01-02 09:00", "aaaa1111"),
+            ("2026-01-03T10:00:00", "bbbb3333"),
+            ("2025-12-31", "12345678"),
+            ("2025-12-29", "No revision before 2025-12-29"),
+        ] {
+            assert_eq!(
+                resolve(&db, file_id, revision).await,
+                expected,
+                "{}",
+                revision
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_tag() {
+        for tag in ["stable", "v1", "release-2026", "k8s_tools", "aaaa"] {
+            assert!(validate_tag(tag).is_ok(), "{}", tag);
+        }
+        for tag in [
+            "",
+            "12",
+            "a,b",
+            "a b",
+            "tab\t",
+            "2026-01-02",
+            "2026-01-02 10:00",
+            "2026-01-02T10:00:00",
+        ] {
+            assert!(validate_tag(tag).is_err(), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90d"), Some(chrono::Duration::days(90)));
+        assert_eq!(parse_duration("12w"), Some(chrono::Duration::weeks(12)));
+        assert_eq!(parse_duration("24h"), Some(chrono::Duration::hours(24)));
+        assert_eq!(parse_duration("30m"), Some(chrono::Duration::minutes(30)));
+        assert_eq!(parse_duration("45s"), Some(chrono::Duration::seconds(45)));
+        assert_eq!(parse_duration("0d"), Some(chrono::Duration::zero()));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        for duration in [
+            "",
+            "d",
+            "90",
+            "90y",
+            "d90",
+            "1.5d",
+            "90 d",
+            "-5d",
+            "ninety",
+            "90dd",
+            "é",
+            "9999999999999999w",
+        ] {
+            assert_eq!(parse_duration(duration), None, "{}", duration);
+        }
+    }
+}
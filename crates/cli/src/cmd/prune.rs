@@ -0,0 +1,192 @@
+use anyhow::Error;
+use chrono::NaiveDateTime;
+use crosup_entity::modification;
+use crosup_repo::{modification::ModificationRepo, run::RunRepo, run_step::RunStepRepo};
+use inquire::Confirm;
+use owo_colors::OwoColorize;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection};
+
+use super::{get_database_connection, parse_duration};
+
+pub async fn execute_prune(
+    keep: Option<usize>,
+    older_than: Option<String>,
+    yes: bool,
+) -> Result<(), Error> {
+    if keep.is_none() && older_than.is_none() {
+        return Err(Error::msg(format!(
+            "Nothing to prune, please use {} and/or {}",
+            "--keep".bright_green(),
+            "--older-than".bright_green()
+        )));
+    }
+
+    let cutoff = match older_than {
+        Some(older_than) => {
+            let cutoff = parse_duration(&older_than)
+                .and_then(|duration| chrono::Utc::now().naive_utc().checked_sub_signed(duration));
+            Some(cutoff.ok_or(Error::msg(format!(
+                "Invalid duration {}, expected e.g. 90d, 12w or 24h",
+                older_than
+            )))?)
+        }
+        None => None,
+    };
+
+    let db: DatabaseConnection = get_database_connection().await?;
+
+    let modifications = ModificationRepo::new(&db).find_all().await?;
+    let revisions = prunable(modifications, keep, cutoff);
+    let runs = match cutoff {
+        Some(cutoff) => RunRepo::new(&db).find_older_than(cutoff).await?,
+        None => vec![],
+    };
+
+    if revisions.is_empty() && runs.is_empty() {
+        println!("-> Nothing to prune");
+        return Ok(());
+    }
+
+    println!(
+        "-> {} revision(s) and {} run(s) will be deleted",
+        revisions.len().bold().cyan(),
+        runs.len().bold().cyan()
+    );
+
+    if !yes {
+        let answer = Confirm::new("Do you want to prune the history?")
+            .with_default(false)
+            .with_help_message("Press y to prune the history or n to exit")
+            .prompt();
+        if answer.is_err() || !answer.unwrap() {
+            println!("Exiting...");
+            return Ok(());
+        }
+    }
+
+    let run_ids: Vec<i32> = runs.iter().map(|run| run.id).collect();
+    RunStepRepo::new(&db)
+        .delete_by_run_ids(run_ids.clone())
+        .await?;
+    let deleted_runs = RunRepo::new(&db).delete(run_ids).await?;
+
+    RunRepo::new(&db)
+        .detach_modifications(revisions.clone())
+        .await?;
+    let deleted_revisions = ModificationRepo::new(&db).delete(revisions).await?;
+
+    // SQLite keeps the space of the deleted rows until the file is rebuilt
+    if db.get_database_backend() == DatabaseBackend::Sqlite {
+        db.execute_unprepared("VACUUM").await?;
+    }
+
+    println!(
+        "Deleted {} revision(s) and {} run(s) ✨",
+        deleted_revisions.bright_green(),
+        deleted_runs.bright_green()
+    );
+    Ok(())
+}
+
+/// Revisions beyond the `keep` latest ones of each file and older than the
+/// cutoff, the latest revision of a file is always kept for `crosup diff`
+/// and `crosup rollback`.
+fn prunable(
+    modifications: Vec<modification::Model>,
+    keep: Option<usize>,
+    cutoff: Option<NaiveDateTime>,
+) -> Vec<i32> {
+    let keep = keep.unwrap_or(1).max(1);
+    let mut ids = vec![];
+    let mut files: Vec<Vec<modification::Model>> = vec![];
+
+    for modification in modifications {
+        match files.last_mut() {
+            Some(file) if file[0].file_id == modification.file_id => file.push(modification),
+            _ => files.push(vec![modification]),
+        }
+    }
+
+    for file in files {
+        // revisions of a file are sorted from the oldest to the latest
+        let count = file.len().saturating_sub(keep);
+        ids.extend(
+            file.into_iter()
+                .take(count)
+                .filter(|m| cutoff.map(|cutoff| m.timestamp < cutoff).unwrap_or(true))
+                .map(|m| m.id),
+        );
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, day)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn revision(id: i32, file_id: i32, day: u32) -> modification::Model {
+        modification::Model {
+            id,
+            timestamp: date(day),
+            hash: format!("{:064}", id),
+            file_id,
+            previous_id: None,
+            content: String::new(),
+            message: None,
+            tags: None,
+        }
+    }
+
+    /// Five revisions of file 1 from the 1st to the 5th, a single old one
+    /// of file 2.
+    fn modifications() -> Vec<modification::Model> {
+        let mut modifications: Vec<_> = (1..=5).map(|i| revision(i, 1, i as u32)).collect();
+        modifications.push(revision(6, 2, 1));
+        modifications
+    }
+
+    #[test]
+    fn test_prunable_keep() {
+        assert_eq!(prunable(modifications(), Some(2), None), vec![1, 2, 3]);
+        assert_eq!(prunable(modifications(), Some(10), None), Vec::<i32>::new());
+        // the latest revision of each file is kept even with --keep 0
+        assert_eq!(prunable(modifications(), Some(0), None), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_prunable_cutoff() {
+        assert_eq!(
+            prunable(modifications(), None, Some(date(4))),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            prunable(modifications(), None, Some(date(31))),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            prunable(modifications(), None, Some(date(1))),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn test_prunable_keep_and_cutoff() {
+        // only revisions beyond the 2 latest ones and older than the 2nd
+        assert_eq!(prunable(modifications(), Some(2), Some(date(2))), vec![1]);
+        // only revisions older than the 5th beyond the 4 latest ones
+        assert_eq!(prunable(modifications(), Some(4), Some(date(5))), vec![1]);
+        assert_eq!(
+            prunable(modifications(), Some(3), Some(date(31))),
+            vec![1, 2]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::Error;
+use crosup_core::{config::verify_if_config_file_is_present, graph::build_installer_graph};
+use crosup_entity::modification;
+use crosup_repo::{database, file::FileRepo, modification::ModificationRepo};
+use crosup_types::configuration::Configuration;
+use inquire::Confirm;
+use owo_colors::OwoColorize;
+use sea_orm::DatabaseConnection;
+
+use super::{get_database_connection, install::execute_install, print_diff, resolve_revision};
+use crate::types::InstallArgs;
+
+pub async fn execute_rollback(revision: &str, yes: bool, install: bool) -> Result<(), Error> {
+    let (mut current_config, filename, content, is_present) = verify_if_config_file_is_present()?;
+
+    if !is_present {
+        return Err(Error::msg(
+            "No configuration file found in this directory, nothing to roll back",
+        ));
+    }
+
+    let db: DatabaseConnection = get_database_connection().await?;
+    let current_dir = std::env::current_dir()?;
+    let path = format!("{}/{}", current_dir.display(), filename);
+
+    let file = FileRepo::new(&db)
+        .find_by_path(&database::host(), &path)
+        .await?
+        .ok_or(Error::msg(format!(
+            "{} has not been modified, no history available",
+            filename
+        )))?;
+
+    let target = resolve_revision(&db, file.id, revision).await?;
+
+    if target.content == content {
+        println!(
+            "{} is already at revision {}",
+            filename.bold().cyan(),
+            target.hash.bright_green()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "-> Rolling back {} to revision {} ({})",
+        filename.bold().cyan(),
+        target.hash.bright_green(),
+        target.timestamp
+    );
+    print_diff(&content, &target.content);
+
+    if !yes {
+        let answer = Confirm::new("Do you want to restore this revision?")
+            .with_default(false)
+            .with_help_message("Press y to restore the revision or n to exit")
+            .prompt();
+        if answer.is_err() || !answer.unwrap() {
+            println!("Exiting...");
+            return Ok(());
+        }
+    }
+
+    if !install {
+        restore_revision(&db, file.id, &path, &target).await?;
+        print_restored(&filename, &target);
+        return Ok(());
+    }
+
+    // the install records the restored revision, with the rollback message
+    std::fs::write(&path, &target.content)?;
+    print_restored(&filename, &target);
+    let installed = execute_install(InstallArgs {
+        message: Some(rollback_message(&target)),
+        ..Default::default()
+    })
+    .await?;
+    uninstall_removed_tools(&mut current_config, &installed)?;
+
+    Ok(())
+}
+
+fn print_restored(filename: &str, target: &modification::Model) {
+    println!(
+        "Restored {} to revision {} ✨",
+        filename.bright_green(),
+        target.hash.bright_green()
+    );
+}
+
+fn rollback_message(target: &modification::Model) -> String {
+    format!(
+        "Rollback to {}",
+        target.hash.get(..8).unwrap_or(&target.hash)
+    )
+}
+
+/// Writes the content of a revision back to the file and records it as the
+/// latest revision, so the rollback itself shows up in the history.
+pub async fn restore_revision(
+    db: &DatabaseConnection,
+    file_id: i32,
+    path: &str,
+    target: &modification::Model,
+) -> Result<(), Error> {
+    std::fs::write(path, &target.content)?;
+    let message = rollback_message(target);
+    ModificationRepo::new(db)
+        .create(file_id, &target.hash, &target.content, Some(&message))
+        .await?;
+    Ok(())
+}
+
+/// Uninstalls the tools of the previous configuration which are no longer
+/// in the restored one, i.e. which weren't part of its install, tools which
+/// can't be uninstalled are reported.
+fn uninstall_removed_tools(
+    previous_config: &mut Configuration,
+    installed: &[String],
+) -> Result<(), Error> {
+    let (_, previous) = build_installer_graph(previous_config, None)?;
+
+    for installer in previous
+        .iter()
+        .filter(|x| !installed.iter().any(|y| y == x.name()))
+    {
+        if let Err(e) = installer.uninstall() {
+            println!(
+                "-> Failed to uninstall {}: {}",
+                installer.name().bright_green(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use anyhow::Error;
+use crosup_core::{config::verify_if_config_file_is_present, graph::build_installer_graph};
+use owo_colors::OwoColorize;
+
+pub fn execute_uninstall(tools: Vec<String>) -> Result<(), Error> {
+    let (mut config, _, _, _) = verify_if_config_file_is_present()?;
+    let (_, installers) = build_installer_graph(&mut config, None)?;
+
+    for tool_name in tools {
+        match installers
+            .iter()
+            .find(|installer| installer.name() == tool_name)
+        {
+            Some(installer) => installer.uninstall()?,
+            None => println!(
+                "-> {} not found in the configuration file, skipping",
+                tool_name.bright_green()
+            ),
+        }
+    }
+
+    Ok(())
+}
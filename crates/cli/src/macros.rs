@@ -1,34 +1,59 @@
 macro_rules! install {
-    ($args: ident, $config: ident, $session: expr) => {
-        match $args.tools {
-            Some(ref tools) => {
-                for tool_name in tools {
-                    let tool_name = tool_name.replace("ble.sh", "blesh");
-                    let mut default_config = Configuration::default();
-                    let (default_graph, default_installers) =
-                        build_installer_graph(&mut default_config, $session.clone());
+    ($args: ident, $config: ident, $session: expr, $steps: ident) => {
+        // the tools picked with --interactive, whatever the arguments
+        if let Some(selected) = &$args.selected {
+            let (graph, installers) = build_installer_graph(&mut $config, $session.clone())?;
+            let mut visited = vec![false; graph.size()];
+            let mut result = Ok(());
+            for tool in installers
+                .into_iter()
+                .filter(|installer| selected.iter().any(|x| x == installer.name()))
+            {
+                result = graph.install(tool, &mut visited);
+                if result.is_err() {
+                    break;
+                }
+            }
+            $steps.extend(graph.steps());
+            result?;
+        } else {
+            match $args.tools {
+                Some(ref tools) => {
+                    for tool_name in tools {
+                        let tool_name = tool_name.replace("ble.sh", "blesh");
+                        let mut default_config = default_configuration();
+                        let (default_graph, default_installers) =
+                            build_installer_graph(&mut default_config, $session.clone())?;
 
-                    let mut visited = vec![false; default_graph.size()];
-                    if let Some(tool) = default_installers
-                        .into_iter()
-                        .find(|installer| installer.name() == tool_name)
-                    {
-                        default_graph.install(tool, &mut visited)?;
-                        continue;
-                    }
+                        let mut visited = vec![false; default_graph.size()];
+                        if let Some(tool) = default_installers
+                            .into_iter()
+                            .find(|installer| installer.name() == tool_name)
+                        {
+                            let result = default_graph.install(tool, &mut visited);
+                            $steps.extend(default_graph.steps());
+                            result?;
+                            continue;
+                        }
 
-                    let (graph, installers) = build_installer_graph(&mut $config, $session.clone());
-                    let tool = installers
-                        .into_iter()
-                        .find(|installer| installer.name() == tool_name)
-                        .unwrap();
-                    let mut visited = vec![false; graph.size()];
-                    graph.install(tool, &mut visited)?;
+                        let (graph, installers) =
+                            build_installer_graph(&mut $config, $session.clone())?;
+                        let tool = installers
+                            .into_iter()
+                            .find(|installer| installer.name() == tool_name)
+                            .unwrap();
+                        let mut visited = vec![false; graph.size()];
+                        let result = graph.install(tool, &mut visited);
+                        $steps.extend(graph.steps());
+                        result?;
+                    }
+                }
+                None => {
+                    let (graph, _) = build_installer_graph(&mut $config, $session.clone())?;
+                    let result = graph.install_all();
+                    $steps.extend(graph.steps());
+                    result?;
                 }
-            }
-            None => {
-                let (graph, _) = build_installer_graph(&mut $config, $session.clone());
-                graph.install_all()?;
             }
         }
     };
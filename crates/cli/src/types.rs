@@ -2,11 +2,18 @@
 pub struct InstallArgs {
     pub tools: Option<Vec<String>>,
     pub ask: bool,
+    pub interactive: bool,
+    /// Tools picked with `--interactive`, installed with their dependencies
+    /// instead of the whole configuration.
+    pub selected: Option<Vec<String>>,
     pub remote_is_present: bool,
     pub remote: Option<String>,
     pub port: Option<u16>,
     pub username: Option<String>,
     pub inventory: Option<String>,
+    pub package_manager: Option<String>,
+    pub message: Option<String>,
+    pub tags: Vec<String>,
 }
 
 #[derive(Clone, Default)]
@@ -15,3 +22,19 @@ pub struct SearchArgs {
     pub channel: String,
     pub max_results: u32,
 }
+
+#[derive(Clone, Default)]
+pub struct ImportArgs {
+    pub from_system: bool,
+    pub brewfile: Option<String>,
+    pub toml: bool,
+    pub output: Option<String>,
+    pub remote: Option<String>,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct ExportArgs {
+    pub output: Option<String>,
+}
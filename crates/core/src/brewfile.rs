@@ -0,0 +1,221 @@
+use crosup_types::brew::{BrewConfiguration, Package, Tap};
+use indexmap::IndexMap;
+
+/// Quoted arguments of a Brewfile line, e.g. `["hashicorp/tap", "https://..."]`
+/// for `tap "hashicorp/tap", "https://..."`, with the trailing comment removed.
+fn arguments(line: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut current: Option<(char, String)> = None;
+    for c in line.chars() {
+        current = match current {
+            Some((quote, value)) if c == quote => {
+                arguments.push(value);
+                None
+            }
+            Some((quote, mut value)) => {
+                value.push(c);
+                Some((quote, value))
+            }
+            None if c == '#' => break,
+            None if c == '"' || c == '\'' => Some((c, String::new())),
+            None => None,
+        };
+    }
+    arguments
+}
+
+/// Install options of a `brew` line, `args: ["with-openssl", "HEAD"]` maps
+/// to `(["--with-openssl"], true)`.
+fn install_options(line: &str) -> (Vec<String>, bool) {
+    let options = match line.split_once("args:") {
+        Some((_, rest)) => arguments(rest.split(']').next().unwrap_or_default()),
+        None => vec![],
+    };
+    let head = options.iter().any(|x| x == "HEAD");
+    let args = options
+        .into_iter()
+        .filter(|x| x != "HEAD")
+        .map(|x| format!("--{}", x.trim_start_matches('-')))
+        .collect();
+    (args, head)
+}
+
+/// Translates the `tap`, `brew` and `cask` directives of a Brewfile, the
+/// other lines (`mas`, `vscode`, ...) are returned as unsupported.
+pub fn parse(content: &str) -> (BrewConfiguration, Vec<String>) {
+    let mut pkg = IndexMap::new();
+    let mut tap = IndexMap::new();
+    let mut unsupported = vec![];
+
+    for line in content.lines().map(|x| x.trim()) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let directive = line
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .next()
+            .unwrap_or_default();
+        let arguments = arguments(line);
+        let name = match arguments.first() {
+            Some(name) => name.clone(),
+            None => {
+                unsupported.push(line.to_string());
+                continue;
+            }
+        };
+        match directive {
+            "tap" => {
+                tap.insert(
+                    name.clone(),
+                    Tap {
+                        name,
+                        url: arguments.get(1).cloned(),
+                    },
+                );
+            }
+            "brew" => {
+                let (args, head) = install_options(line);
+                pkg.insert(
+                    name.clone(),
+                    Package {
+                        name,
+                        args: Some(args).filter(|x| !x.is_empty()),
+                        head: head.then_some(true),
+                        ..Default::default()
+                    },
+                );
+            }
+            "cask" => {
+                pkg.insert(
+                    name.clone(),
+                    Package {
+                        name,
+                        cask: Some(true),
+                        ..Default::default()
+                    },
+                );
+            }
+            _ => unsupported.push(line.to_string()),
+        }
+    }
+
+    let config = BrewConfiguration {
+        pkgs: None,
+        pkg: Some(pkg).filter(|x| !x.is_empty()),
+        tap: Some(tap).filter(|x| !x.is_empty()),
+    };
+    (config, unsupported)
+}
+
+/// Generates a Brewfile from the brew blocks of a configuration, taps
+/// first, then formulae and casks, like `brew bundle dump`.
+pub fn render(brew: &IndexMap<String, BrewConfiguration>) -> String {
+    let mut taps = vec![];
+    let mut formulae = vec![];
+    let mut casks = vec![];
+
+    for config in brew.values() {
+        for (name, tap) in config.tap.clone().unwrap_or_default() {
+            taps.push(match tap.url {
+                Some(url) => format!("tap \"{}\", \"{}\"", name, url),
+                None => format!("tap \"{}\"", name),
+            });
+        }
+        for name in config.pkgs.clone().unwrap_or_default() {
+            formulae.push(format!("brew \"{}\"", name));
+        }
+        for (name, pkg) in config.pkg.clone().unwrap_or_default() {
+            if pkg.cask.unwrap_or(false) {
+                casks.push(format!("cask \"{}\"", name));
+                continue;
+            }
+            let mut options: Vec<String> = pkg
+                .args
+                .unwrap_or_default()
+                .iter()
+                .map(|x| format!("\"{}\"", x.trim_start_matches('-')))
+                .collect();
+            if pkg.head.unwrap_or(false) {
+                options.push("\"HEAD\"".into());
+            }
+            formulae.push(match options.is_empty() {
+                true => format!("brew \"{}\"", name),
+                false => format!("brew \"{}\", args: [{}]", name, options.join(", ")),
+            });
+        }
+    }
+
+    let mut lines: Vec<String> = vec![];
+    for line in taps.into_iter().chain(formulae).chain(casks) {
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    lines.into_iter().map(|x| x + "\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BREWFILE: &str = r#"# Brewfile
+tap "homebrew/bundle"
+tap "user/tap", "https://gitlab.com/user/homebrew-tap.git"
+brew "git"
+brew "mysql", restart_service: true # database
+brew "hashicorp/tap/terraform"
+brew "neovim", args: ["HEAD", "with-luajit"]
+cask "firefox", args: { appdir: "~/Applications" }
+mas "Xcode", id: 497799835
+"#;
+
+    #[test]
+    fn test_parse() {
+        let (config, unsupported) = parse(BREWFILE);
+        let tap = config.tap.unwrap();
+        assert_eq!(
+            tap.keys().collect::<Vec<_>>(),
+            vec!["homebrew/bundle", "user/tap"]
+        );
+        assert_eq!(
+            tap["user/tap"].url.as_deref(),
+            Some("https://gitlab.com/user/homebrew-tap.git")
+        );
+
+        let pkg = config.pkg.unwrap();
+        assert_eq!(
+            pkg.keys().collect::<Vec<_>>(),
+            vec![
+                "git",
+                "mysql",
+                "hashicorp/tap/terraform",
+                "neovim",
+                "firefox"
+            ]
+        );
+        assert_eq!(pkg["firefox"].cask, Some(true));
+        assert_eq!(pkg["firefox"].args, None);
+        assert_eq!(pkg["git"].cask, None);
+        assert_eq!(pkg["mysql"].args, None);
+        assert_eq!(pkg["neovim"].args, Some(vec!["--with-luajit".into()]));
+        assert_eq!(pkg["neovim"].head, Some(true));
+        assert_eq!(unsupported, vec![r#"mas "Xcode", id: 497799835"#]);
+    }
+
+    #[test]
+    fn test_render() {
+        let (config, _) = parse(BREWFILE);
+        let brew = [("install".to_string(), config)].into_iter().collect();
+        assert_eq!(
+            render(&brew),
+            r#"tap "homebrew/bundle"
+tap "user/tap", "https://gitlab.com/user/homebrew-tap.git"
+brew "git"
+brew "mysql"
+brew "hashicorp/tap/terraform"
+brew "neovim", args: ["with-luajit", "HEAD"]
+cask "firefox"
+"#
+        );
+    }
+}
@@ -2,14 +2,30 @@ use anyhow::Error;
 use owo_colors::OwoColorize;
 use std::path::Path;
 
+use crate::platform::Platform;
 use crosup_types::{
     configuration::Configuration, inventory::Inventory, CROSFILE_HCL, CROSFILE_TOML, INVENTORY_HCL,
     INVENTORY_TOML,
 };
 
+/// Default configuration for the current machine, used when no Crosfile is present.
+pub fn default_configuration() -> Configuration {
+    let platform = Platform::detect(None).unwrap_or_default();
+    Configuration::default_for(&platform.id, &platform.family)
+}
+
+/// Parses the content of a configuration file, in hcl or toml depending on
+/// its name.
+pub fn parse_configuration(filename: &str, content: &str) -> Result<Configuration, Error> {
+    match filename.ends_with(".hcl") {
+        true => Ok(hcl::from_str(content)?),
+        false => Ok(toml::from_str(content)?),
+    }
+}
+
 pub fn verify_if_config_file_is_present() -> Result<(Configuration, String, String, bool), Error> {
     if !Path::new(CROSFILE_HCL).exists() && !Path::new(CROSFILE_TOML).exists() {
-        let config = Configuration::default();
+        let config = default_configuration();
         return Ok((
             config.clone(),
             CROSFILE_HCL.into(),
@@ -33,8 +49,19 @@ pub fn verify_if_config_file_is_present() -> Result<(Configuration, String, Stri
     return Ok((config, CROSFILE_TOML.into(), content, true));
 }
 
-pub fn verify_if_inventory_config_file_is_present() -> Result<Inventory, Error> {
-    if !Path::new(INVENTORY_HCL).exists() && !Path::new(INVENTORY_TOML).exists() {
+/// Reads the inventory file given with `--inventory`, or the default
+/// Inventory.hcl/Inventory.toml of the current directory, returns the
+/// inventory with its filename and content.
+pub fn verify_if_inventory_config_file_is_present(
+    path: Option<&str>,
+) -> Result<(Inventory, String, String), Error> {
+    let filename = match path {
+        Some(path) => path,
+        None if Path::new(INVENTORY_HCL).exists() => INVENTORY_HCL,
+        None => INVENTORY_TOML,
+    };
+
+    if !Path::new(filename).exists() {
         return Err(anyhow::anyhow!(format!(
             "Inventory file not found, please create one using {}",
             "crosup init --inventory".bright_green()
@@ -42,14 +69,10 @@ pub fn verify_if_inventory_config_file_is_present() -> Result<Inventory, Error>
     }
 
     let current_dir = std::env::current_dir()?;
-
-    if Path::new(INVENTORY_HCL).exists() {
-        let config = std::fs::read_to_string(current_dir.join(INVENTORY_HCL))?;
-        let config = hcl::from_str(&config)?;
-        return Ok(config);
-    }
-
-    let config = std::fs::read_to_string(current_dir.join(INVENTORY_TOML))?;
-    let config = toml::from_str(&config)?;
-    return Ok(config);
+    let content = std::fs::read_to_string(current_dir.join(filename))?;
+    let config = match filename.ends_with(".hcl") {
+        true => hcl::from_str(&content)?,
+        false => toml::from_str(&content)?,
+    };
+    Ok((config, filename.into(), content))
 }
@@ -0,0 +1,222 @@
+use anyhow::Error;
+use crosup_types::configuration::Configuration;
+use indexmap::IndexMap;
+use toml::{map::Map, Value};
+
+/// Labeled blocks whose entries are reported individually, e.g. the
+/// `pkg "neovim"` entries of a `brew "install"` block.
+const COLLECTIONS: [&str; 6] = ["pkg", "repo", "script", "dotfile", "unit", "tap"];
+
+/// Changes of a single block of the configuration, e.g. `brew.install`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    pub block: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Packages added, removed or changed per provider block between two
+/// configurations, instead of raw text lines.
+pub fn semantic_diff(
+    previous: &Configuration,
+    current: &Configuration,
+) -> Result<Vec<Changes>, Error> {
+    let previous = Value::try_from(previous)?;
+    let current = Value::try_from(current)?;
+    let mut changes = IndexMap::new();
+    compare_tables(
+        "",
+        previous.as_table().unwrap_or(&Map::new()),
+        current.as_table().unwrap_or(&Map::new()),
+        &mut changes,
+    );
+    Ok(changes.into_values().filter(|x| !x.is_empty()).collect())
+}
+
+fn keys<'a>(previous: &'a Map<String, Value>, current: &'a Map<String, Value>) -> Vec<&'a String> {
+    let mut keys: Vec<&String> = previous.keys().collect();
+    keys.extend(current.keys().filter(|x| !previous.contains_key(*x)));
+    keys
+}
+
+fn compare_tables(
+    path: &str,
+    previous: &Map<String, Value>,
+    current: &Map<String, Value>,
+    changes: &mut IndexMap<String, Changes>,
+) {
+    let empty = Map::new();
+    for key in keys(previous, current) {
+        let (a, b) = (previous.get(key), current.get(key));
+        if a == b {
+            continue;
+        }
+        if is_table(a) && is_table(b) && !COLLECTIONS.contains(&key.as_str()) {
+            let path = match path.is_empty() {
+                true => key.clone(),
+                false => format!("{}.{}", path, key),
+            };
+            let a = a.and_then(|x| x.as_table()).unwrap_or(&empty);
+            let b = b.and_then(|x| x.as_table()).unwrap_or(&empty);
+            compare_tables(&path, a, b, changes);
+            continue;
+        }
+
+        // top level settings, e.g. `packages`, are blocks of their own
+        let (block, prefix) = match path.is_empty() {
+            true => (key.clone(), String::new()),
+            false => (path.to_string(), format!("{} ", key)),
+        };
+        let entry = changes.entry(block.clone()).or_insert(Changes {
+            block,
+            ..Default::default()
+        });
+
+        match (a, b) {
+            (Some(Value::Array(a)), Some(Value::Array(b))) => {
+                compare_lists(&prefix, a, b, entry);
+            }
+            (Some(Value::Array(a)), None) => compare_lists(&prefix, a, &[], entry),
+            (None, Some(Value::Array(b))) => compare_lists(&prefix, &[], b, entry),
+            (a, b) if is_table(a) && is_table(b) => {
+                let a = a.and_then(|x| x.as_table()).unwrap_or(&empty);
+                let b = b.and_then(|x| x.as_table()).unwrap_or(&empty);
+                compare_entries(&prefix, a, b, entry);
+            }
+            _ => entry.changed.push(key.clone()),
+        }
+    }
+}
+
+fn is_table(value: Option<&Value>) -> bool {
+    value.map(|x| x.is_table()).unwrap_or(true)
+}
+
+fn compare_lists(prefix: &str, previous: &[Value], current: &[Value], changes: &mut Changes) {
+    let name = |x: &Value| match x.as_str() {
+        Some(x) => format!("{}{}", prefix, x),
+        None => format!("{}{}", prefix, x),
+    };
+    changes
+        .added
+        .extend(current.iter().filter(|x| !previous.contains(x)).map(name));
+    changes
+        .removed
+        .extend(previous.iter().filter(|x| !current.contains(x)).map(name));
+}
+
+fn compare_entries(
+    prefix: &str,
+    previous: &Map<String, Value>,
+    current: &Map<String, Value>,
+    changes: &mut Changes,
+) {
+    for name in keys(previous, current) {
+        match (previous.get(name), current.get(name)) {
+            (None, Some(_)) => changes.added.push(format!("{}{}", prefix, name)),
+            (Some(_), None) => changes.removed.push(format!("{}{}", prefix, name)),
+            (Some(a), Some(b)) if a != b => {
+                let empty = Map::new();
+                let a = a.as_table().unwrap_or(&empty);
+                let b = b.as_table().unwrap_or(&empty);
+                let attributes: Vec<&str> = keys(a, b)
+                    .into_iter()
+                    .filter(|x| a.get(*x) != b.get(*x))
+                    .map(|x| x.as_str())
+                    .collect();
+                changes
+                    .changed
+                    .push(format!("{}{} ({})", prefix, name, attributes.join(", ")));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_semantic_diff() {
+        let previous: Configuration = toml::from_str(
+            r#"
+            packages = ["vim", "git"]
+
+            [brew.install.pkg.bat]
+
+            [brew.install.pkg.neovim]
+            version_check = "nvim"
+
+            [apt.install.pkg.curl]
+            "#,
+        )
+        .unwrap();
+        let current: Configuration = toml::from_str(
+            r#"
+            packages = ["vim", "zig"]
+
+            [brew.install.pkg.neovim]
+            version_check = "vim"
+
+            [brew.install.pkg.terraform]
+
+            [apt.install.pkg.curl]
+            "#,
+        )
+        .unwrap();
+
+        let changes = semantic_diff(&previous, &current).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                Changes {
+                    block: "brew.install".into(),
+                    added: vec!["pkg terraform".into()],
+                    removed: vec!["pkg bat".into()],
+                    changed: vec!["pkg neovim (version_check)".into()],
+                },
+                Changes {
+                    block: "packages".into(),
+                    added: vec!["zig".into()],
+                    removed: vec!["git".into()],
+                    changed: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_diff_new_block() {
+        let previous: Configuration = toml::from_str("packages = [\"vim\"]").unwrap();
+        let current: Configuration = toml::from_str(
+            r#"
+            packages = ["vim"]
+
+            [flatpak.install]
+            remote = "flathub"
+
+            [flatpak.install.pkg."org.mozilla.firefox"]
+            "#,
+        )
+        .unwrap();
+
+        let changes = semantic_diff(&previous, &current).unwrap();
+        assert_eq!(
+            changes,
+            vec![Changes {
+                block: "flatpak.install".into(),
+                added: vec!["pkg org.mozilla.firefox".into()],
+                removed: vec![],
+                changed: vec!["remote".into()],
+            }]
+        );
+    }
+}
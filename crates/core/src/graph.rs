@@ -1,20 +1,54 @@
 use anyhow::Error;
 use crosup_installers::{
-    apk::ApkInstaller, apt::AptInstaller, brew::BrewInstaller, curl::CurlInstaller,
-    dnf::DnfInstaller, emerge::EmergeInstaller, fleek::FleekInstaller, git::GitInstaller,
-    home_manager::HomeManagerInstaller, nix::NixInstaller, pacman::PacmanInstaller,
-    slackpkg::SlackpkgInstaller, yum::YumInstaller, zypper::ZypperInstaller, Installer,
-};
-use crosup_macros::{
-    add_vertex, add_vertex_with_condition, convert_generic_installer, downcast_installer,
+    apk::ApkInstaller,
+    apt::AptInstaller,
+    archive::ArchiveInstaller,
+    brew::{BrewInstaller, BrewTapInstaller},
+    cargo::CargoInstaller,
+    curl::CurlInstaller,
+    dnf::DnfInstaller,
+    download,
+    emerge::EmergeInstaller,
+    file::FileInstaller,
+    flatpak::FlatpakInstaller,
+    fleek::FleekInstaller,
+    git::GitInstaller,
+    go::GoInstaller,
+    home_manager::HomeManagerInstaller,
+    nix::NixInstaller,
+    npm::NpmInstaller,
+    pacman::PacmanInstaller,
+    pipx::PipxInstaller,
+    release::ReleaseInstaller,
+    rpm_ostree::RpmOstreeInstaller,
+    runtime::RuntimeInstaller,
+    service::ServiceInstaller,
+    slackpkg::SlackpkgInstaller,
+    xbps::XbpsInstaller,
+    yum::YumInstaller,
+    zypper::ZypperInstaller,
+    Installer,
 };
+use crosup_macros::{add_vertex, convert_generic_installer, downcast_installer};
 use crosup_types::{
+    brew,
     configuration::Configuration,
-    curl::{default_brew_installer, default_nix_installer},
+    curl::{
+        default_brew_installer, default_mise_installer, default_nix_installer,
+        default_rustup_installer,
+    },
+    file, flatpak, npm,
+    release::default_asdf_installer,
+    service,
 };
-use os_release::OsRelease;
 use owo_colors::OwoColorize;
 use ssh2::Session;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::platform::Platform;
 
 #[derive(Clone)]
 pub struct Vertex {
@@ -23,6 +57,7 @@ pub struct Vertex {
     provider: String,
     apt: Option<AptInstaller>,
     brew: Option<BrewInstaller>,
+    brew_tap: Option<BrewTapInstaller>,
     curl: Option<CurlInstaller>,
     git: Option<GitInstaller>,
     nix: Option<NixInstaller>,
@@ -35,6 +70,18 @@ pub struct Vertex {
     slackpkg: Option<SlackpkgInstaller>,
     fleek: Option<FleekInstaller>,
     home_manager: Option<HomeManagerInstaller>,
+    cargo: Option<CargoInstaller>,
+    go: Option<GoInstaller>,
+    pipx: Option<PipxInstaller>,
+    npm: Option<NpmInstaller>,
+    release: Option<ReleaseInstaller>,
+    archive: Option<ArchiveInstaller>,
+    runtime: Option<RuntimeInstaller>,
+    xbps: Option<XbpsInstaller>,
+    rpm_ostree: Option<RpmOstreeInstaller>,
+    file: Option<FileInstaller>,
+    service: Option<ServiceInstaller>,
+    flatpak: Option<FlatpakInstaller>,
 }
 
 impl From<Box<dyn Installer + 'static>> for Vertex {
@@ -49,6 +96,7 @@ impl From<Box<dyn Installer + 'static>> for Vertex {
             provider: installer.provider().to_string(),
             apt: downcast_installer!("apt", installer, AptInstaller),
             brew: downcast_installer!("brew", installer, BrewInstaller),
+            brew_tap: downcast_installer!("brew-tap", installer, BrewTapInstaller),
             curl: downcast_installer!("curl", installer, CurlInstaller),
             git: downcast_installer!("git", installer, GitInstaller),
             nix: downcast_installer!("nix", installer, NixInstaller),
@@ -61,6 +109,18 @@ impl From<Box<dyn Installer + 'static>> for Vertex {
             slackpkg: downcast_installer!("slackpkg", installer, SlackpkgInstaller),
             fleek: downcast_installer!("fleek", installer, FleekInstaller),
             home_manager: downcast_installer!("home-manager", installer, HomeManagerInstaller),
+            cargo: downcast_installer!("cargo", installer, CargoInstaller),
+            go: downcast_installer!("go", installer, GoInstaller),
+            pipx: downcast_installer!("pipx", installer, PipxInstaller),
+            npm: downcast_installer!("npm", installer, NpmInstaller),
+            release: downcast_installer!("release", installer, ReleaseInstaller),
+            archive: downcast_installer!("archive", installer, ArchiveInstaller),
+            runtime: downcast_installer!("runtime", installer, RuntimeInstaller),
+            xbps: downcast_installer!("xbps", installer, XbpsInstaller),
+            rpm_ostree: downcast_installer!("rpm-ostree", installer, RpmOstreeInstaller),
+            file: downcast_installer!("file", installer, FileInstaller),
+            service: downcast_installer!("service", installer, ServiceInstaller),
+            flatpak: downcast_installer!("flatpak", installer, FlatpakInstaller),
         }
     }
 }
@@ -70,6 +130,7 @@ impl Into<Box<dyn Installer>> for Vertex {
         match self.provider.as_str() {
             "apt" => Box::new(self.apt.unwrap()),
             "brew" => Box::new(self.brew.unwrap()),
+            "brew-tap" => Box::new(self.brew_tap.unwrap()),
             "curl" => Box::new(self.curl.unwrap()),
             "git" => Box::new(self.git.unwrap()),
             "nix" => Box::new(self.nix.unwrap()),
@@ -82,6 +143,18 @@ impl Into<Box<dyn Installer>> for Vertex {
             "slackpkg" => Box::new(self.slackpkg.unwrap()),
             "fleek" => Box::new(self.fleek.unwrap()),
             "home-manager" => Box::new(self.home_manager.unwrap()),
+            "cargo" => Box::new(self.cargo.unwrap()),
+            "go" => Box::new(self.go.unwrap()),
+            "pipx" => Box::new(self.pipx.unwrap()),
+            "npm" => Box::new(self.npm.unwrap()),
+            "release" => Box::new(self.release.unwrap()),
+            "archive" => Box::new(self.archive.unwrap()),
+            "runtime" => Box::new(self.runtime.unwrap()),
+            "xbps" => Box::new(self.xbps.unwrap()),
+            "rpm-ostree" => Box::new(self.rpm_ostree.unwrap()),
+            "file" => Box::new(self.file.unwrap()),
+            "service" => Box::new(self.service.unwrap()),
+            "flatpak" => Box::new(self.flatpak.unwrap()),
             _ => panic!("Unknown installer: {}", self.name),
         }
     }
@@ -93,10 +166,34 @@ pub struct Edge {
     to: usize,
 }
 
+/// Outcome of the installation of a single tool, see `InstallerGraph::steps`.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub tool: String,
+    pub provider: String,
+    pub command: Option<String>,
+    pub exit_status: i32,
+    pub duration: Duration,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+}
+
+/// Number of lines of output kept for each step.
+const TAIL_LINES: usize = 20;
+
+fn tail(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    match lines.is_empty() {
+        true => None,
+        false => Some(lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n")),
+    }
+}
+
 #[derive(Clone)]
 pub struct InstallerGraph {
     vertices: Vec<Vertex>,
     edges: Vec<Edge>,
+    steps: Arc<Mutex<Vec<Step>>>,
 }
 
 impl Into<Vec<Box<dyn Installer>>> for InstallerGraph {
@@ -105,69 +202,101 @@ impl Into<Vec<Box<dyn Installer>>> for InstallerGraph {
     }
 }
 
-pub fn autodetect_installer(config: &mut Configuration) {
+/// Converts the generic install block to the package manager of the
+/// platform, detected once by `build_installer_graph`.
+pub fn autodetect_installer(
+    config: &mut Configuration,
+    platform: Result<&Platform, &Error>,
+) -> Result<(), Error> {
     if let Some(generic_install) = &config.install {
-        // detect linux
-        if cfg!(target_os = "linux") {
-            // determine linux distribution using os-release
-            if let Ok(os_release) = OsRelease::new() {
-                let os = os_release.id.to_lowercase();
-                let os = os.as_str();
-
-                let package_manager = match os {
-                    "ubuntu" | "debian" | "linuxmint" | "pop" | "elementary" | "zorin" => {
-                        convert_generic_installer!(config, generic_install, apt);
-                        "apt-get"
-                    }
-                    "fedora" | "centos" | "rhel" | "rocky" | "amazon" => {
-                        convert_generic_installer!(config, generic_install, dnf);
-                        "dnf"
-                    }
-                    "opensuse" | "sles" => {
-                        convert_generic_installer!(config, generic_install, zypper);
-                        "zypper"
-                    }
-                    "arch" | "manjaro" => {
-                        convert_generic_installer!(config, generic_install, pacman);
-                        "pacman"
-                    }
-                    "gentoo" => {
-                        convert_generic_installer!(config, generic_install, emerge);
-                        "emerge"
-                    }
-                    "alpine" => {
-                        convert_generic_installer!(config, generic_install, apk);
-                        "apk"
-                    }
-                    "slackware" => {
-                        convert_generic_installer!(config, generic_install, slackpkg);
-                        "slackpkg"
-                    }
-                    _ => panic!("Unsupported OS: {}", os),
-                };
+        let package_manager = match generic_install.package_manager.clone() {
+            Some(package_manager) => package_manager,
+            None => {
+                let platform = platform.map_err(|e| Error::msg(format!("{:#}", e)))?;
+                match platform.family.as_str() {
+                    "macos" => println!("-> Detected OS: 🍎 macOS"),
+                    _ => println!("-> Detected OS:🐧 {}", platform.pretty_name.magenta()),
+                }
+                match platform.package_manager() {
+                    Some(package_manager) => package_manager.to_string(),
+                    None => return Err(Error::msg(format!(
+                        "Unsupported OS: {}, the generic install block can't be used on this distribution, use a provider block (nix, brew, ...) or set package_manager instead",
+                        platform.pretty_name
+                    ))),
+                }
+            }
+        };
 
-                let os_pretty = os_release.pretty_name;
-                println!("-> Detected OS:🐧 {}", os_pretty.magenta());
-                println!(
-                    "-> Using package manager: 📦 {}",
-                    package_manager.bright_green()
-                );
+        let package_manager = match package_manager.as_str() {
+            "apt" | "apt-get" => {
+                convert_generic_installer!(config, generic_install, apt);
+                "apt-get"
             }
-        }
-        if cfg!(target_os = "macos") {
-            println!("-> Detected OS: 🍎 macOS");
-            println!("-> Using package manager: 📦 {}", "brew".bright_green());
-            convert_generic_installer!(config, generic_install, brew);
-        }
+            "yum" => {
+                convert_generic_installer!(config, generic_install, yum);
+                "yum"
+            }
+            "dnf" => {
+                convert_generic_installer!(config, generic_install, dnf);
+                "dnf"
+            }
+            "rpm-ostree" | "rpm_ostree" => {
+                convert_generic_installer!(config, generic_install, rpm_ostree);
+                "rpm-ostree"
+            }
+            "zypper" => {
+                convert_generic_installer!(config, generic_install, zypper);
+                "zypper"
+            }
+            "pacman" => {
+                convert_generic_installer!(config, generic_install, pacman);
+                "pacman"
+            }
+            "emerge" => {
+                convert_generic_installer!(config, generic_install, emerge);
+                "emerge"
+            }
+            "apk" => {
+                convert_generic_installer!(config, generic_install, apk);
+                "apk"
+            }
+            "slackpkg" => {
+                convert_generic_installer!(config, generic_install, slackpkg);
+                "slackpkg"
+            }
+            "xbps" => {
+                convert_generic_installer!(config, generic_install, xbps);
+                "xbps"
+            }
+            "brew" | "homebrew" => {
+                convert_generic_installer!(config, generic_install, brew);
+                "brew"
+            }
+            package_manager => {
+                return Err(Error::msg(format!(
+                    "Unsupported package manager: {}, expected one of apt, yum, dnf, rpm-ostree, zypper, pacman, emerge, apk, slackpkg, xbps or brew",
+                    package_manager
+                )))
+            }
+        };
+
+        println!(
+            "-> Using package manager: 📦 {}",
+            package_manager.bright_green()
+        );
     }
+    Ok(())
 }
 
 pub fn build_installer_graph(
     config: &mut Configuration,
     session: Option<Session>,
-) -> (InstallerGraph, Vec<Box<dyn Installer>>) {
+) -> Result<(InstallerGraph, Vec<Box<dyn Installer>>), Error> {
     let mut graph = InstallerGraph::new();
 
+    let platform = Platform::detect(config.distro.as_ref());
+    autodetect_installer(config, platform.as_ref())?;
+
     if config.clone().nix.is_some()
         || config.clone().fleek.is_some()
         || config.clone().packages.is_some()
@@ -211,23 +340,61 @@ pub fn build_installer_graph(
         }
     }
 
-    autodetect_installer(config);
-
-    if cfg!(target_os = "linux") {
-        // determine linux distribution using os-release
-        if let Ok(os_release) = OsRelease::new() {
-            let os = os_release.id.to_lowercase();
-            let os = os.as_str();
+    if config.clone().cargo.is_some() {
+        if let Some(curl) = config.clone().curl {
+            if !curl
+                .into_iter()
+                .any(|(_, y)| y.script.contains_key("rustup"))
+            {
+                let rustup = default_rustup_installer();
+                graph.add_vertex(Vertex::from(Box::new(CurlInstaller {
+                    name: rustup.name.clone(),
+                    session: session.clone(),
+                    ..CurlInstaller::from(rustup.clone())
+                }) as Box<dyn Installer>));
+            }
+        } else {
+            let rustup = default_rustup_installer();
+            graph.add_vertex(Vertex::from(Box::new(CurlInstaller {
+                name: rustup.name.clone(),
+                session: session.clone(),
+                ..CurlInstaller::from(rustup.clone())
+            }) as Box<dyn Installer>));
+        }
+    }
 
-            match os {
-                "ubuntu" | "debian" | "linuxmint" | "pop" | "elementary" | "zorin" => {
-                    add_vertex!(graph, AptInstaller, config, apt, pkg, session);
-                }
-                _ => {}
-            };
+    if let Some(installer) = config
+        .clone()
+        .runtime
+        .and_then(|x| x.get("install").cloned())
+    {
+        let curl = config.clone().curl.unwrap_or_default();
+        let release = config.clone().release.unwrap_or_default();
+        match installer.manager.unwrap_or("mise".into()).as_str() {
+            "mise" if !curl.values().any(|y| y.script.contains_key("mise")) => {
+                let mise = default_mise_installer();
+                graph.add_vertex(Vertex::from(Box::new(CurlInstaller {
+                    name: mise.name.clone(),
+                    session: session.clone(),
+                    ..CurlInstaller::from(mise.clone())
+                }) as Box<dyn Installer>));
+            }
+            "asdf" if !release.values().any(|y| y.pkg.contains_key("asdf")) => {
+                let asdf = default_asdf_installer();
+                graph.add_vertex(Vertex::from(Box::new(ReleaseInstaller {
+                    name: asdf.name.clone(),
+                    session: session.clone(),
+                    ..ReleaseInstaller::from(asdf.clone())
+                }) as Box<dyn Installer>));
+            }
+            _ => {}
         }
     }
 
+    if apt_is_enabled(config, platform.as_ref().ok()) {
+        add_vertex!(graph, AptInstaller, config, apt, pkg, session);
+    }
+
     add_vertex!(graph, CurlInstaller, config, curl, script, session);
     add_vertex!(graph, GitInstaller, config, git, repo, session);
     add_vertex!(graph, NixInstaller, config, nix, pkg, session);
@@ -238,8 +405,105 @@ pub fn build_installer_graph(
     add_vertex!(graph, PacmanInstaller, config, pacman, pkg, session);
     add_vertex!(graph, EmergeInstaller, config, emerge, pkg, session);
     add_vertex!(graph, SlackpkgInstaller, config, slackpkg, pkg, session);
+    add_vertex!(graph, XbpsInstaller, config, xbps, pkg, session);
+    add_vertex!(graph, RpmOstreeInstaller, config, rpm_ostree, pkg, session);
     add_vertex!(graph, FleekInstaller, config, fleek, pkg, session);
-    add_vertex_with_condition!(graph, BrewInstaller, config, brew, pkg, session);
+    add_vertex!(graph, CargoInstaller, config, cargo, pkg, session);
+    add_vertex!(graph, GoInstaller, config, go, pkg, session);
+    add_vertex!(graph, PipxInstaller, config, pipx, pkg, session);
+    add_vertex!(graph, ReleaseInstaller, config, release, pkg, session);
+    add_vertex!(graph, ArchiveInstaller, config, archive, pkg, session);
+
+    if let Some(npm) = &config.npm {
+        if let Some(installer) = npm.get("install") {
+            installer.pkg.iter().for_each(|(name, x)| {
+                let pkg = npm::Package {
+                    prefix: x.prefix.clone().or(installer.prefix.clone()),
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(NpmInstaller {
+                    name: name.clone(),
+                    session: session.clone(),
+                    ..NpmInstaller::from(pkg)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
+
+    if let Some(runtime) = &config.runtime {
+        if let Some(installer) = runtime.get("install") {
+            let manager = installer.manager.clone().unwrap_or("mise".into());
+            installer.versions.iter().for_each(|(name, version)| {
+                graph.add_vertex(Vertex::from(Box::new(RuntimeInstaller {
+                    session: session.clone(),
+                    ..RuntimeInstaller::new(name, version, &manager)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
+
+    if let Some(file) = &config.file {
+        if let Some(installer) = file.get("install") {
+            installer.dotfile.iter().for_each(|(name, x)| {
+                let source = match &installer.source_dir {
+                    Some(dir) if !x.source.starts_with('/') => {
+                        format!("{}/{}", dir.trim_end_matches('/'), x.source)
+                    }
+                    _ => x.source.clone(),
+                };
+                let dotfile = file::Dotfile {
+                    source,
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(FileInstaller {
+                    name: name.clone(),
+                    session: session.clone(),
+                    ..FileInstaller::from(dotfile)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
+
+    if let Some(flatpak) = &config.flatpak {
+        if let Some(installer) = flatpak.get("install") {
+            installer.pkg.iter().for_each(|(name, x)| {
+                let pkg = flatpak::Package {
+                    name: name.clone(),
+                    remote: x.remote.clone().or(installer.remote.clone()),
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(FlatpakInstaller {
+                    session: session.clone(),
+                    ..FlatpakInstaller::from(pkg)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
+
+    if let Some(brew) = &config.brew {
+        if let Some(installer) = brew.get("install") {
+            installer.tap.iter().flatten().for_each(|(name, x)| {
+                let tap = brew::Tap {
+                    name: name.clone(),
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(BrewTapInstaller {
+                    session: session.clone(),
+                    ..BrewTapInstaller::from(tap)
+                }) as Box<dyn Installer>));
+            });
+            installer.pkg.iter().flatten().for_each(|(name, x)| {
+                let pkg = brew::Package {
+                    name: name.clone(),
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(BrewInstaller {
+                    session: session.clone(),
+                    ..BrewInstaller::from(pkg)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
 
     if let Some(package) = config.clone().packages {
         package.iter().for_each(|name| {
@@ -254,9 +518,36 @@ pub fn build_installer_graph(
         });
     }
 
+    if let Some(service) = &config.service {
+        if let Some(installer) = service.get("install") {
+            installer.unit.iter().for_each(|(name, x)| {
+                let service = service::Service {
+                    name: name.clone(),
+                    ..x.clone()
+                };
+                graph.add_vertex(Vertex::from(Box::new(ServiceInstaller {
+                    session: session.clone(),
+                    ..ServiceInstaller::from(service)
+                }) as Box<dyn Installer>));
+            });
+        }
+    }
+
     setup_dependencies(&mut graph);
 
-    (graph.clone(), graph.into())
+    Ok((graph.clone(), graph.into()))
+}
+
+/// apt blocks are only installed on the Debian family, unless apt is chosen
+/// with `--package-manager` or the `package_manager` key of the install
+/// block, e.g. on a derivative which isn't detected.
+fn apt_is_enabled(config: &Configuration, platform: Option<&Platform>) -> bool {
+    let requested = config
+        .install
+        .as_ref()
+        .and_then(|install| install.package_manager.as_deref());
+    matches!(requested, Some("apt") | Some("apt-get"))
+        || platform.and_then(|x| x.package_manager()) == Some("apt")
 }
 
 fn setup_dependencies(graph: &mut InstallerGraph) {
@@ -278,6 +569,7 @@ impl InstallerGraph {
         Self {
             vertices: vec![],
             edges: vec![],
+            steps: Arc::new(Mutex::new(vec![])),
         }
     }
 
@@ -325,14 +617,204 @@ impl InstallerGraph {
             self.install(self.vertices[edge.to].clone().into(), visited)?;
         }
 
-        package.install()?;
+        download::take_journal();
+        let started_at = Instant::now();
+        let result = package.install();
+        self.record(package.as_ref(), started_at.elapsed(), &result);
+        result?;
 
         visited[index] = true;
 
+        for service in self.services_of(index) {
+            self.install(self.vertices[service].clone().into(), visited)?;
+        }
+
         Ok(())
     }
 
+    /// Services set up for a tool, e.g. `docker-service` for `docker`, which
+    /// are installed along with it even though nothing depends on them.
+    fn services_of(&self, index: usize) -> Vec<usize> {
+        let name = format!("{}-service", self.vertices[index].name);
+        self.vertices
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.provider == "service" && x.name == name)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn record(&self, package: &dyn Installer, duration: Duration, result: &Result<(), Error>) {
+        let journal = download::take_journal().unwrap_or_default();
+        let step = Step {
+            tool: package.name().to_string(),
+            provider: package.provider().to_string(),
+            command: Some(journal.command).filter(|x| !x.is_empty()),
+            exit_status: match result {
+                Ok(_) => 0,
+                Err(_) => journal.exit_status.filter(|x| *x != 0).unwrap_or(1),
+            },
+            duration,
+            stdout: journal.output.as_deref().and_then(tail),
+            stderr: match result {
+                Ok(_) => None,
+                Err(e) => tail(&format!("{:#}", e)),
+            },
+        };
+        self.steps.lock().unwrap().push(step);
+    }
+
+    /// Steps run by `install` so far, in the order they finished.
+    pub fn steps(&self) -> Vec<Step> {
+        self.steps.lock().unwrap().clone()
+    }
+
     pub fn size(&self) -> usize {
         self.vertices.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail(""), None);
+        assert_eq!(tail("  \n\n"), None);
+        assert_eq!(tail("one\ntwo\n"), Some("one\ntwo".into()));
+
+        let text: Vec<String> = (1..=30).map(|i| format!("line {}", i)).collect();
+        let lines = tail(&text.join("\n")).unwrap();
+        assert_eq!(lines.lines().count(), TAIL_LINES);
+        assert!(lines.starts_with("line 11\n"));
+        assert!(lines.ends_with("line 30"));
+    }
+
+    #[test]
+    fn test_record() {
+        let graph = InstallerGraph::new();
+        let vim = AptInstaller {
+            name: "vim".into(),
+            provider: "apt".into(),
+            ..Default::default()
+        };
+
+        download::exec("echo fetching", None).unwrap();
+        download::exec("echo installing", None).unwrap();
+        graph.record(&vim, Duration::from_millis(1500), &Ok(()));
+
+        // nothing run since the last step
+        graph.record(
+            &vim,
+            Duration::from_millis(10),
+            &Err(Error::msg("vim is not available")),
+        );
+
+        // the exit status of the failed command is kept
+        assert!(download::exec("echo downloading; exit 42", None).is_err());
+        graph.record(
+            &vim,
+            Duration::from_millis(20),
+            &Err(Error::msg("Failed to install vim")),
+        );
+
+        let steps = graph.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            (steps[0].tool.as_str(), steps[0].provider.as_str()),
+            ("vim", "apt")
+        );
+        assert_eq!(
+            steps[0].command.as_deref(),
+            Some("echo fetching\necho installing")
+        );
+        assert_eq!(steps[0].stdout.as_deref(), Some("fetching\ninstalling"));
+        assert_eq!((steps[0].exit_status, steps[0].stderr.clone()), (0, None));
+        assert_eq!(steps[0].duration, Duration::from_millis(1500));
+
+        assert_eq!(steps[1].command, None);
+        assert_eq!(steps[1].stdout, None);
+        assert_eq!(steps[1].exit_status, 1);
+        assert_eq!(steps[1].stderr.as_deref(), Some("vim is not available"));
+
+        assert_eq!(steps[2].stdout.as_deref(), Some("downloading"));
+        assert_eq!(steps[2].exit_status, 42);
+    }
+
+    #[test]
+    fn test_apt_is_enabled() {
+        let fedora = Platform {
+            family: "fedora".into(),
+            ..Default::default()
+        };
+        let debian = Platform {
+            family: "debian".into(),
+            ..Default::default()
+        };
+        let config: Configuration = toml::from_str("[apt.install.pkg.vim]").unwrap();
+        assert!(apt_is_enabled(&config, Some(&debian)));
+        assert!(!apt_is_enabled(&config, Some(&fedora)));
+        assert!(!apt_is_enabled(&config, None));
+
+        let config: Configuration = toml::from_str(
+            r#"
+            [install]
+            package_manager = "apt-get"
+
+            [install.pkg.vim]
+            "#,
+        )
+        .unwrap();
+        assert!(apt_is_enabled(&config, Some(&fedora)));
+        assert!(apt_is_enabled(&config, None));
+    }
+
+    #[test]
+    fn test_package_manager_override() {
+        let mut config: Configuration = toml::from_str(
+            r#"
+            [install]
+            package_manager = "apt"
+
+            [install.pkg.vim]
+            "#,
+        )
+        .unwrap();
+        let (graph, _) = build_installer_graph(&mut config, None).unwrap();
+        assert!(graph.contains("vim"));
+        assert!(config.apt.unwrap().contains_key("install"));
+    }
+
+    #[test]
+    fn test_services_of() {
+        let mut config: Configuration = toml::from_str(
+            r#"
+            [install]
+            package_manager = "apt"
+
+            [install.pkg.docker]
+
+            [install.pkg.vim]
+
+            [service.install.unit.docker]
+            groups = ["docker"]
+            "#,
+        )
+        .unwrap();
+        let (graph, _) = build_installer_graph(&mut config, None).unwrap();
+        let docker = graph.vertices.iter().position(|x| x.name == "docker");
+        let vim = graph.vertices.iter().position(|x| x.name == "vim");
+        let service = graph
+            .vertices
+            .iter()
+            .position(|x| x.name == "docker-service");
+
+        assert_eq!(graph.services_of(docker.unwrap()), vec![service.unwrap()]);
+        assert!(graph.services_of(vim.unwrap()).is_empty());
+        assert!(graph
+            .edges
+            .iter()
+            .any(|x| x.from == service.unwrap() && x.to == docker.unwrap()));
+    }
+}
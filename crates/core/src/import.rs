@@ -0,0 +1,418 @@
+use anyhow::Error;
+use crosup_installers::{brew::BREW_PATH, download};
+use crosup_types::{
+    apt::{self, AptConfiguration},
+    brew::{self, BrewConfiguration},
+    configuration::Configuration,
+    dnf::{self, DnfConfiguration},
+    flatpak::{self, FlatpakConfiguration},
+    nix::{self, NixConfiguration},
+    pacman::{self, PacmanConfiguration},
+};
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+
+use crate::brewfile;
+
+const NIX_PROFILE: &str = ". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh 2>/dev/null; nix --extra-experimental-features \"nix-command flakes\"";
+
+/// Runs a command on the target machine, `None` if the command is missing or
+/// fails, in which case the corresponding provider is left out.
+fn capture(command: &str, session: Option<Session>) -> Option<String> {
+    download::capture(command, session).ok()
+}
+
+fn found(count: usize, source: &str) {
+    println!(
+        "-> Found {} packages with {}",
+        count.bold().cyan(),
+        source.bright_green()
+    );
+}
+
+/// Packages marked as manually installed, minus the packages which come
+/// with the base system (priority required, important or standard).
+pub fn parse_apt(showmanual: &str, priorities: &str) -> Vec<String> {
+    let base: Vec<&str> = priorities
+        .lines()
+        .filter_map(|line| {
+            let (package, priority) = line.trim().split_once(' ')?;
+            match priority.trim() {
+                "required" | "important" | "standard" => Some(package),
+                _ => None,
+            }
+        })
+        .collect();
+    showmanual
+        .lines()
+        .map(|x| x.trim().split(':').next().unwrap_or_default())
+        .filter(|x| !x.is_empty() && !base.contains(x))
+        .map(|x| x.to_string())
+        .collect()
+}
+
+/// Package names of `dnf history userinstalled`, which lists NEVRAs, e.g.
+/// `vim-enhanced-2:9.0.1677-1.fc38.x86_64`.
+pub fn parse_dnf(userinstalled: &str) -> Vec<String> {
+    userinstalled
+        .lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty() && !x.contains(' '))
+        .filter_map(|nevra| nevra.rsplitn(3, '-').nth(2))
+        .map(|x| x.to_string())
+        .collect()
+}
+
+/// Flake reference to install a nix profile element with, e.g.
+/// `flake:nixpkgs` and `legacyPackages.x86_64-linux.hello` give `nixpkgs#hello`.
+fn flake_ref(url: &str, attribute: &str) -> (String, String) {
+    let url = url.strip_prefix("flake:").unwrap_or(url);
+    let mut parts: Vec<&str> = attribute.split('.').collect();
+    if parts.len() > 2 && ["legacyPackages", "packages"].contains(&parts[0]) {
+        parts.drain(..2);
+    }
+    let attribute = parts.join(".");
+    match attribute.as_str() {
+        "" | "default" => {
+            let name = url.trim_end_matches('/').rsplit('/').next().unwrap_or(url);
+            (name.to_string(), url.to_string())
+        }
+        _ => (
+            parts.last().unwrap_or(&"").to_string(),
+            format!("{}#{}", url, attribute),
+        ),
+    }
+}
+
+/// Elements of `nix profile list`, as `(name, flake)`. Both the current
+/// output (`Name:`, `Flake attribute:`, ... blocks) and the former one-line
+/// format are supported, elements not installed from a flake are skipped.
+pub fn parse_nix_profile(list: &str) -> Vec<(String, String)> {
+    let mut elements = vec![];
+    let mut name: Option<String> = None;
+    let mut attribute: Option<String> = None;
+    for line in list.lines() {
+        let line = line.trim();
+        if let Some(value) = line.strip_prefix("Name:") {
+            name = Some(value.trim().to_string());
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Flake attribute:") {
+            attribute = Some(value.trim().to_string());
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Original flake URL:") {
+            if let Some(attribute) = attribute.take() {
+                let (default, flake) = flake_ref(value.trim(), &attribute);
+                elements.push((name.take().unwrap_or(default), flake));
+            }
+            continue;
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 && parts[0].parse::<usize>().is_ok() {
+            if let Some((url, attribute)) = parts[1].split_once('#') {
+                elements.push(flake_ref(url, attribute));
+            }
+        }
+    }
+    elements
+        .into_iter()
+        .filter(|(name, _)| name != "home-manager-path")
+        .collect()
+}
+
+/// `(application, origin)` pairs of `flatpak list --app --columns=application,origin`.
+pub fn parse_flatpak(list: &str) -> Vec<(String, String)> {
+    list.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+fn import_apt(session: Option<Session>) -> Option<IndexMap<String, AptConfiguration>> {
+    let showmanual = capture("apt-mark showmanual", session.clone())?;
+    let priorities = capture(
+        "dpkg-query -W -f=\"\\${Package} \\${Priority}\\n\"",
+        session,
+    )
+    .unwrap_or_default();
+    let packages = parse_apt(&showmanual, &priorities);
+    found(packages.len(), "apt");
+    let pkg = packages
+        .into_iter()
+        .map(|name| {
+            let package = apt::Package {
+                name: name.clone(),
+                packages: Some(vec![name.clone()]),
+                ..Default::default()
+            };
+            (name, package)
+        })
+        .collect();
+    Some(
+        [("install".into(), AptConfiguration { pkg })]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn import_dnf(session: Option<Session>) -> Option<IndexMap<String, DnfConfiguration>> {
+    let userinstalled = capture("dnf history userinstalled", session)?;
+    let packages = parse_dnf(&userinstalled);
+    found(packages.len(), "dnf");
+    let pkg = packages
+        .into_iter()
+        .map(|name| {
+            let package = dnf::Package {
+                name: name.clone(),
+                packages: Some(vec![name.clone()]),
+                ..Default::default()
+            };
+            (name, package)
+        })
+        .collect();
+    Some(
+        [("install".into(), DnfConfiguration { pkg })]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn import_pacman(session: Option<Session>) -> Option<IndexMap<String, PacmanConfiguration>> {
+    let explicit = capture("pacman -Qqe", session)?;
+    let packages: Vec<&str> = explicit.split_whitespace().collect();
+    found(packages.len(), "pacman");
+    let pkg = packages
+        .into_iter()
+        .map(|name| {
+            let package = pacman::Package {
+                name: name.into(),
+                packages: Some(vec![name.into()]),
+                ..Default::default()
+            };
+            (name.to_string(), package)
+        })
+        .collect();
+    Some(
+        [("install".into(), PacmanConfiguration { pkg })]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn import_brew(session: Option<Session>) -> Option<IndexMap<String, BrewConfiguration>> {
+    let leaves = capture(&format!("{} brew leaves", BREW_PATH), session.clone())?;
+    let casks = capture(&format!("{} brew list --cask", BREW_PATH), session).unwrap_or_default();
+    let mut pkg = IndexMap::new();
+    for name in leaves.split_whitespace() {
+        pkg.insert(
+            name.to_string(),
+            brew::Package {
+                name: name.into(),
+                ..Default::default()
+            },
+        );
+    }
+    for name in casks.split_whitespace() {
+        pkg.insert(
+            name.to_string(),
+            brew::Package {
+                name: name.into(),
+                cask: Some(true),
+                ..Default::default()
+            },
+        );
+    }
+    found(pkg.len(), "brew");
+    Some(
+        [(
+            "install".into(),
+            BrewConfiguration {
+                pkg: Some(pkg),
+                ..Default::default()
+            },
+        )]
+        .into_iter()
+        .collect(),
+    )
+}
+
+fn import_nix(session: Option<Session>) -> Option<IndexMap<String, NixConfiguration>> {
+    let list = capture(&format!("{} profile list", NIX_PROFILE), session)?;
+    let elements = parse_nix_profile(&list);
+    if elements.is_empty() {
+        return None;
+    }
+    found(elements.len(), "nix profile");
+    let pkg = elements
+        .into_iter()
+        .map(|(name, flake)| {
+            let package = nix::Package {
+                name: name.clone(),
+                flake,
+                ..Default::default()
+            };
+            (name, package)
+        })
+        .collect();
+    Some(
+        [("install".into(), NixConfiguration { pkg })]
+            .into_iter()
+            .collect(),
+    )
+}
+
+fn import_home_manager(session: Option<Session>) -> Option<Vec<String>> {
+    let home_nix = capture("cat $HOME/.config/home-manager/home.nix", session)?;
+    let packages = crosup_nix::list_packages(&home_nix).ok()?;
+    found(packages.len(), "home-manager");
+    Some(packages)
+}
+
+fn import_flatpak(session: Option<Session>) -> Option<IndexMap<String, FlatpakConfiguration>> {
+    let list = capture("flatpak list --app --columns=application,origin", session)?;
+    let apps = parse_flatpak(&list);
+    found(apps.len(), "flatpak");
+    let pkg = apps
+        .into_iter()
+        .map(|(app_id, origin)| {
+            let package = flatpak::Package {
+                name: app_id.clone(),
+                remote: Some(origin).filter(|x| x != "flathub"),
+                ..Default::default()
+            };
+            (app_id, package)
+        })
+        .collect();
+    Some(
+        [("install".into(), FlatpakConfiguration { remote: None, pkg })]
+            .into_iter()
+            .collect(),
+    )
+}
+
+/// Inspects the packages installed on the local machine, or on the remote
+/// one when a session is given, and returns the matching configuration.
+pub fn import_from_system(session: Option<Session>) -> Result<Configuration, Error> {
+    let config = Configuration {
+        packages: import_home_manager(session.clone()),
+        install: None,
+        distro: None,
+        brew: import_brew(session.clone()),
+        git: None,
+        nix: import_nix(session.clone()),
+        curl: None,
+        apt: import_apt(session.clone()),
+        yum: None,
+        dnf: import_dnf(session.clone()),
+        zypper: None,
+        apk: None,
+        pacman: import_pacman(session.clone()),
+        emerge: None,
+        slackpkg: None,
+        xbps: None,
+        rpm_ostree: None,
+        fleek: None,
+        cargo: None,
+        go: None,
+        pipx: None,
+        npm: None,
+        release: None,
+        archive: None,
+        runtime: None,
+        file: None,
+        service: None,
+        flatpak: import_flatpak(session),
+    };
+    Ok(config)
+}
+
+/// Translates a Brewfile into a configuration with a single brew block.
+pub fn import_from_brewfile(content: &str) -> Result<Configuration, Error> {
+    let (brew, unsupported) = brewfile::parse(content);
+    for line in unsupported {
+        println!("-> Skipping unsupported Brewfile entry {}", line.yellow());
+    }
+    found(brew.pkg.as_ref().map(|x| x.len()).unwrap_or(0), "Brewfile");
+    let config = Configuration {
+        packages: None,
+        install: None,
+        distro: None,
+        brew: Some([("install".into(), brew)].into_iter().collect()),
+        git: None,
+        nix: None,
+        curl: None,
+        apt: None,
+        yum: None,
+        dnf: None,
+        zypper: None,
+        apk: None,
+        pacman: None,
+        emerge: None,
+        slackpkg: None,
+        xbps: None,
+        rpm_ostree: None,
+        fleek: None,
+        cargo: None,
+        go: None,
+        pipx: None,
+        npm: None,
+        release: None,
+        archive: None,
+        runtime: None,
+        file: None,
+        service: None,
+        flatpak: None,
+    };
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_apt() {
+        let showmanual = "bash\ncurl\nneovim\nlibc6:amd64\n";
+        let priorities = "bash required\ncurl optional\nneovim optional\nlibc6 required\n";
+        assert_eq!(parse_apt(showmanual, priorities), vec!["curl", "neovim"]);
+    }
+
+    #[test]
+    fn test_parse_dnf() {
+        let output = "Packages installed by user\nvim-enhanced-2:9.0.1677-1.fc38.x86_64\ngit-core-2.41.0-1.fc38.x86_64\n";
+        assert_eq!(parse_dnf(output), vec!["vim-enhanced", "git-core"]);
+    }
+
+    #[test]
+    fn test_parse_nix_profile() {
+        let current = "Name:               hello\n\
+                       Flake attribute:    legacyPackages.x86_64-linux.hello\n\
+                       Original flake URL: flake:nixpkgs\n\
+                       Locked flake URL:   github:NixOS/nixpkgs/0a254180b4cad6be45aa46dce896bdb8db5d2930\n\
+                       Store paths:        /nix/store/a0kd9fyxvd3x3p7zbzhpaaf3d3icpcdk-hello-2.12.1\n\
+                       \n\
+                       Name:               cachix\n\
+                       Flake attribute:    packages.x86_64-linux.default\n\
+                       Original flake URL: github:cachix/cachix\n\
+                       Locked flake URL:   github:cachix/cachix/4d2a5c1d\n\
+                       Store paths:        /nix/store/a2ds1pyxyj0x8wk2ymm6m9yd3sgpbp2c-cachix-1.6\n";
+        assert_eq!(
+            parse_nix_profile(current),
+            vec![
+                ("hello".to_string(), "nixpkgs#hello".to_string()),
+                ("cachix".to_string(), "github:cachix/cachix".to_string()),
+            ]
+        );
+
+        let former = "0 flake:nixpkgs#legacyPackages.x86_64-linux.ripgrep github:NixOS/nixpkgs/0a25 /nix/store/xx-ripgrep-13.0.0\n\
+                      1 - - /nix/store/xx-home-manager-path\n";
+        assert_eq!(
+            parse_nix_profile(former),
+            vec![("ripgrep".to_string(), "nixpkgs#ripgrep".to_string())]
+        );
+    }
+}
@@ -1,2 +1,6 @@
+pub mod brewfile;
 pub mod config;
+pub mod diff;
 pub mod graph;
+pub mod import;
+pub mod platform;
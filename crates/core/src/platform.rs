@@ -0,0 +1,183 @@
+use anyhow::Error;
+use indexmap::IndexMap;
+use os_release::OsRelease;
+use std::path::Path;
+
+/// Distribution IDs, as found in `ID` and `ID_LIKE` of os-release, and the
+/// family they belong to. Entries can be added or overridden in the Crosfile
+/// with a `distro` block, e.g. `distro { kali = "debian" }`.
+pub const DISTROS: &[(&str, &str)] = &[
+    ("debian", "debian"),
+    ("ubuntu", "debian"),
+    ("linuxmint", "debian"),
+    ("pop", "debian"),
+    ("elementary", "debian"),
+    ("zorin", "debian"),
+    ("kali", "debian"),
+    ("raspbian", "debian"),
+    ("neon", "debian"),
+    ("deepin", "debian"),
+    ("devuan", "debian"),
+    ("parrot", "debian"),
+    ("fedora", "fedora"),
+    ("rhel", "fedora"),
+    ("centos", "fedora"),
+    ("rocky", "fedora"),
+    ("almalinux", "fedora"),
+    ("ol", "fedora"),
+    ("amzn", "fedora"),
+    ("amazon", "fedora"),
+    ("nobara", "fedora"),
+    ("opensuse", "suse"),
+    ("opensuse-leap", "suse"),
+    ("opensuse-tumbleweed", "suse"),
+    ("sles", "suse"),
+    ("suse", "suse"),
+    ("arch", "arch"),
+    ("manjaro", "arch"),
+    ("endeavouros", "arch"),
+    ("garuda", "arch"),
+    ("artix", "arch"),
+    ("gentoo", "gentoo"),
+    ("alpine", "alpine"),
+    ("slackware", "slackware"),
+    ("void", "void"),
+    ("nixos", "nixos"),
+];
+
+/// `VARIANT_ID`s of the Fedora Atomic desktops, which can only layer packages
+/// with rpm-ostree.
+const OSTREE_VARIANTS: &[&str] = &["silverblue", "kinoite", "sericea", "onyx", "iot", "coreos"];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Platform {
+    pub id: String,
+    pub family: String,
+    pub version: String,
+    pub arch: String,
+    pub pretty_name: String,
+    pub ostree: bool,
+}
+
+/// Resolves a distribution ID against the Crosfile overrides first, then
+/// against the built-in table.
+fn lookup(id: &str, overrides: Option<&IndexMap<String, String>>) -> Option<String> {
+    if let Some(family) = overrides.and_then(|x| x.get(id)) {
+        return Some(family.clone());
+    }
+    DISTROS
+        .iter()
+        .find(|(x, _)| *x == id)
+        .map(|(_, family)| family.to_string())
+}
+
+impl Platform {
+    pub fn detect(overrides: Option<&IndexMap<String, String>>) -> Result<Self, Error> {
+        if cfg!(target_os = "macos") {
+            return Ok(Self {
+                id: "macos".into(),
+                family: "macos".into(),
+                arch: std::env::consts::ARCH.into(),
+                pretty_name: "macOS".into(),
+                ..Default::default()
+            });
+        }
+
+        let os_release = OsRelease::new()?;
+        let mut platform = Self::from_os_release(&os_release, overrides);
+        platform.ostree = platform.ostree || Path::new("/run/ostree-booted").exists();
+        Ok(platform)
+    }
+
+    /// Resolves `ID`, then each entry of `ID_LIKE`, to a family. Unknown
+    /// distributions are their own family.
+    pub fn from_os_release(
+        os_release: &OsRelease,
+        overrides: Option<&IndexMap<String, String>>,
+    ) -> Self {
+        let id = os_release.id.trim_matches('"').to_lowercase();
+        let id_like = os_release.id_like.trim_matches('"').to_lowercase();
+        let family = std::iter::once(id.as_str())
+            .chain(id_like.split_whitespace())
+            .find_map(|x| lookup(x, overrides))
+            .unwrap_or(id.clone());
+        let variant = os_release
+            .extra
+            .get("VARIANT_ID")
+            .map(|x| x.trim_matches('"').to_lowercase())
+            .unwrap_or_default();
+
+        Self {
+            id,
+            family,
+            version: os_release.version_id.clone(),
+            arch: std::env::consts::ARCH.into(),
+            pretty_name: os_release.pretty_name.clone(),
+            ostree: OSTREE_VARIANTS.contains(&variant.as_str()),
+        }
+    }
+
+    /// Provider the generic `install` block is converted to on this platform.
+    pub fn package_manager(&self) -> Option<&'static str> {
+        match self.family.as_str() {
+            "debian" => Some("apt"),
+            "fedora" if self.ostree => Some("rpm-ostree"),
+            "fedora" => Some("dnf"),
+            "suse" => Some("zypper"),
+            "arch" => Some("pacman"),
+            "gentoo" => Some("emerge"),
+            "alpine" => Some("apk"),
+            "slackware" => Some("slackpkg"),
+            "void" => Some("xbps"),
+            "macos" => Some("brew"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn os_release(content: &str) -> OsRelease {
+        content.lines().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_os_release() {
+        let void = Platform::from_os_release(&os_release("ID=\"void\""), None);
+        assert_eq!(void.family, "void");
+        assert_eq!(void.package_manager(), Some("xbps"));
+
+        let kali = os_release("ID=kali\nID_LIKE=debian\nVERSION_ID=\"2024.1\"");
+        let kali = Platform::from_os_release(&kali, None);
+        assert_eq!(kali.id, "kali");
+        assert_eq!(kali.family, "debian");
+        assert_eq!(kali.version, "2024.1");
+
+        let alma = os_release("ID=\"almalinux\"\nID_LIKE=\"rhel centos fedora\"");
+        let alma = Platform::from_os_release(&alma, None);
+        assert_eq!(alma.package_manager(), Some("dnf"));
+
+        let endeavour = os_release("ID=endeavouros\nID_LIKE=arch");
+        let endeavour = Platform::from_os_release(&endeavour, None);
+        assert_eq!(endeavour.package_manager(), Some("pacman"));
+
+        let silverblue = os_release("ID=fedora\nVARIANT_ID=silverblue");
+        let silverblue = Platform::from_os_release(&silverblue, None);
+        assert_eq!(silverblue.package_manager(), Some("rpm-ostree"));
+
+        let nixos = Platform::from_os_release(&os_release("ID=nixos"), None);
+        assert_eq!(nixos.package_manager(), None);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let overrides: IndexMap<String, String> =
+            [("mydistro".into(), "arch".into())].into_iter().collect();
+        let platform = os_release("ID=mydistro\nID_LIKE=debian");
+        let platform = Platform::from_os_release(&platform, Some(&overrides));
+        assert_eq!(platform.family, "arch");
+        assert_eq!(platform.package_manager(), Some("pacman"));
+    }
+}
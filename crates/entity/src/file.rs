@@ -1,21 +1,33 @@
 use sea_orm::entity::prelude::*;
 use sea_orm::DeriveEntityModel;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "file")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub name: String,
     pub path: String,
+    /// Machine the file was recorded on, empty for rows recorded before
+    /// crosup tracked it.
+    #[serde(default)]
+    pub host: String,
     #[sea_orm(column_type = "Timestamp")]
     pub created_at: chrono::NaiveDateTime,
+    pub project_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::modification::Entity")]
     Modification,
+    #[sea_orm(
+        belongs_to = "super::project::Entity",
+        from = "Column::ProjectId",
+        to = "super::project::Column::Id"
+    )]
+    Project,
 }
 
 // `Related` trait has to be implemented by hand
@@ -25,4 +37,10 @@ impl Related<super::modification::Entity> for Entity {
     }
 }
 
+impl Related<super::project::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Project.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
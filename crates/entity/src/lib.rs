@@ -1,2 +1,5 @@
 pub mod file;
 pub mod modification;
+pub mod project;
+pub mod run;
+pub mod run_step;
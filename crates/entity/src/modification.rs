@@ -1,7 +1,8 @@
 use sea_orm::entity::prelude::*;
 use sea_orm::DeriveEntityModel;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "modification")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -13,6 +14,10 @@ pub struct Model {
     pub previous_id: Option<i32>,
     #[sea_orm(column_type = "Text")]
     pub content: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub message: Option<String>,
+    /// Comma-separated names, e.g. `stable,v1`.
+    pub tags: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -0,0 +1,60 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::DeriveEntityModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub file_id: i32,
+    pub modification_id: Option<i32>,
+    pub host: String,
+    pub status: String,
+    #[sea_orm(column_type = "Timestamp")]
+    pub started_at: chrono::NaiveDateTime,
+    pub duration_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    File,
+    Modification,
+    RunStep,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::File => Entity::belongs_to(super::file::Entity)
+                .from(Column::FileId)
+                .to(super::file::Column::Id)
+                .into(),
+            Self::Modification => Entity::belongs_to(super::modification::Entity)
+                .from(Column::ModificationId)
+                .to(super::modification::Column::Id)
+                .into(),
+            Self::RunStep => Entity::has_many(super::run_step::Entity).into(),
+        }
+    }
+}
+
+impl Related<super::file::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::File.def()
+    }
+}
+
+impl Related<super::modification::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Modification.def()
+    }
+}
+
+impl Related<super::run_step::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RunStep.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
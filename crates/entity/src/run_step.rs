@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+use sea_orm::DeriveEntityModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "run_step")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub run_id: i32,
+    pub tool: String,
+    pub provider: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub command: Option<String>,
+    pub exit_status: i32,
+    pub duration_ms: i64,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stdout: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stderr: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::run::Entity",
+        from = "Column::RunId",
+        to = "super::run::Column::Id"
+    )]
+    Run,
+}
+
+impl Related<super::run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Run.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -6,7 +6,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct ApkInstaller {
@@ -16,6 +16,7 @@ pub struct ApkInstaller {
     pub apk_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub interactive: bool,
     pub provider: String,
@@ -29,6 +30,7 @@ impl From<Package> for ApkInstaller {
             packages: pkg.packages,
             apk_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "apk".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             interactive: pkg.interactive.unwrap_or(false),
             ..Default::default()
@@ -37,6 +39,10 @@ impl From<Package> for ApkInstaller {
 }
 
 impl ApkInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.apk_dependencies.is_empty() {
             return Ok(());
@@ -73,6 +79,7 @@ impl Installer for ApkInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -91,6 +98,7 @@ impl Installer for ApkInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
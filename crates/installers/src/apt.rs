@@ -6,7 +6,7 @@ use std::{any::Any, io::BufRead, process::Stdio};
 use crosup_macros::{apt_install, check_version, exec_bash, exec_bash_with_output, exec_sudo};
 use crosup_types::apt::Package;
 
-use super::Installer;
+use super::{download, shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct AptInstaller {
@@ -21,6 +21,7 @@ pub struct AptInstaller {
     pub apt_update: Option<bool>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub provider: String,
     pub session: Option<Session>,
@@ -38,13 +39,27 @@ impl From<Package> for AptInstaller {
             packages: pkg.packages,
             apt_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "apt".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             ..Default::default()
         }
     }
 }
 
+fn deb_arch(arch: &str) -> Option<&'static str> {
+    match arch {
+        "x86_64" => Some("x64"),
+        "aarch64" => Some("arm64"),
+        "armv7l" | "armv6l" | "armhf" => Some("armhf"),
+        _ => None,
+    }
+}
+
 impl AptInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.apt_dependencies.is_empty() {
             return Ok(());
@@ -102,41 +117,29 @@ impl AptInstaller {
         Ok(())
     }
 
-    pub fn install_from_url(&self) -> Result<(), Error> {
+    /// Replaces the `{arch}` placeholder of the url with the architecture of
+    /// the target machine, named like most `.deb` downloads (e.g. VS Code):
+    /// `x64`, `arm64` or `armhf`.
+    fn url(&self) -> Result<String, Error> {
         let url = self.url.clone().unwrap();
-        let package_name = format!("{}.deb", self.name.clone());
-
-        let command = format!("wget -c {} -O {}", url, package_name);
-        println!("   Running {}", command.bright_green());
-
-        if let Some(session) = &self.session {
-            crosup_ssh::exec(session.clone(), &command)?;
-            return Ok(());
+        if !url.contains("{arch}") {
+            return Ok(url);
         }
-
-        let mut child = std::process::Command::new("wget")
-            .arg("-c")
-            .arg(url)
-            .arg("-O")
-            .arg(package_name.clone())
-            .stdout(Stdio::piped())
-            .spawn()?;
-
-        let stdout = child.stdout.take().unwrap();
-        let stdout = std::io::BufReader::new(stdout);
-        for line in stdout.lines() {
-            println!("   {}", line.unwrap());
+        let (_, arch) = download::platform(self.session.clone())?;
+        match deb_arch(&arch) {
+            Some(deb_arch) => Ok(url.replace("{arch}", deb_arch)),
+            None => Err(Error::msg(format!(
+                "{} is not available for {}",
+                self.name, arch
+            ))),
         }
+    }
 
-        let output = child.wait_with_output()?;
+    pub fn install_from_url(&self) -> Result<(), Error> {
+        let url = self.url()?;
+        let package_name = format!("{}.deb", self.name.clone());
 
-        if !output.status.success() {
-            println!("-> Failed to download {}", self.name().bright_green());
-            if !output.stdout.is_empty() {
-                println!("{}", String::from_utf8_lossy(&output.stdout));
-            }
-            return Err(Error::msg(format!("Failed to install {}", self.name())));
-        }
+        download::download(&url, &package_name, self.session.clone())?;
 
         let command = format!("sudo apt-get install -y ./{}", package_name);
         println!("   Running {}", command.bright_green());
@@ -186,6 +189,7 @@ impl Installer for AptInstaller {
                 "-> {} is already installed, skipping",
                 self.name().bright_green()
             );
+            self.shell_init()?;
             return Ok(());
         }
         let from_url = match self.url.clone() {
@@ -227,6 +231,7 @@ impl Installer for AptInstaller {
         if let Some(_) = self.url.clone() {
             self.install_from_url()?;
             self.postinstall()?;
+            self.shell_init()?;
             return Ok(());
         }
 
@@ -238,6 +243,7 @@ impl Installer for AptInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
@@ -279,3 +285,27 @@ impl Installer for AptInstaller {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deb_arch() {
+        assert_eq!(deb_arch("x86_64"), Some("x64"));
+        assert_eq!(deb_arch("aarch64"), Some("arm64"));
+        assert_eq!(deb_arch("armv7l"), Some("armhf"));
+        assert_eq!(deb_arch("riscv64"), None);
+    }
+
+    #[test]
+    fn test_shell_init_from_install_block() {
+        let pkg = crosup_types::install::Package {
+            name: "direnv".into(),
+            shell_init: Some(vec!["eval \"$(direnv hook bash)\"".into()]),
+            ..Default::default()
+        };
+        let installer = AptInstaller::from(Into::<Package>::into(pkg));
+        assert_eq!(installer.shell_init, vec!["eval \"$(direnv hook bash)\""]);
+    }
+}
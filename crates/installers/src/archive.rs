@@ -0,0 +1,349 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_bash_with_output};
+use crosup_types::archive::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{download, shell, Installer};
+
+pub const MANIFEST_DIR: &str = "$HOME/.local/share/crosup/archives";
+
+/// Files and directories created by an install, one path per line, the
+/// directories prefixed with `dir `. Only those are removed on uninstall, a
+/// shared prefix such as `/usr/local` is left as it was.
+#[derive(Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub files: Vec<String>,
+    pub dirs: Vec<String>,
+}
+
+impl Manifest {
+    pub fn parse(content: &str) -> Self {
+        let mut manifest = Self::default();
+        for line in content.lines().filter(|x| !x.trim().is_empty()) {
+            match line.strip_prefix("dir ") {
+                Some(dir) => manifest.dirs.push(dir.to_string()),
+                None => manifest.files.push(line.to_string()),
+            }
+        }
+        manifest
+    }
+
+    pub fn render(&self) -> String {
+        self.dirs
+            .iter()
+            .map(|dir| format!("dir {}\n", dir))
+            .chain(self.files.iter().map(|file| format!("{}\n", file)))
+            .collect()
+    }
+
+    /// Removes the files, then the directories from the deepest to the
+    /// top, `rmdir` keeps those which still contain files of other tools.
+    pub fn removal_script(&self) -> String {
+        let mut dirs: Vec<&String> = self.dirs.iter().collect();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.matches('/').count()));
+        let mut commands: Vec<String> = self
+            .files
+            .iter()
+            .map(|file| format!("rm -f \"{}\"", file))
+            .collect();
+        commands.extend(
+            dirs.into_iter()
+                .map(|dir| format!("rmdir \"{}\" 2>/dev/null", dir)),
+        );
+        commands.push("true".into());
+        commands.join("; ")
+    }
+}
+
+/// Lists `dir <path>` for the given directory and its parents which don't
+/// exist yet, before they are created with `mkdir -p`.
+fn missing_dirs(dir: &str) -> String {
+    format!(
+        "d=\"{}\"; while [ ! -d \"$d\" ]; do echo \"dir $d\"; d=$(dirname \"$d\"); done",
+        dir
+    )
+}
+
+#[derive(Default, Clone)]
+pub struct ArchiveInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub url: String,
+    pub sha256: Option<String>,
+    pub prefix: Option<String>,
+    pub strip_components: u32,
+    pub bin: Vec<String>,
+    pub bin_dir: String,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for ArchiveInstaller {
+    fn from(pkg: Package) -> Self {
+        Self {
+            name: pkg.name,
+            version: "latest".into(),
+            dependencies: pkg.depends_on.unwrap_or(vec![]),
+            url: pkg.url,
+            sha256: pkg.sha256,
+            prefix: pkg.prefix,
+            strip_components: pkg.strip_components.unwrap_or(0),
+            bin: pkg.bin.unwrap_or(vec![]),
+            bin_dir: pkg.bin_dir.unwrap_or("~/.local/bin".into()),
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "archive".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ArchiveInstaller {
+    pub fn prefix(&self) -> String {
+        match self.prefix.clone() {
+            Some(prefix) => prefix.replacen("~", "$HOME", 1),
+            None => format!("$HOME/.local/opt/{}", self.name),
+        }
+    }
+
+    fn bin_dir(&self) -> String {
+        self.bin_dir.replacen("~", "$HOME", 1)
+    }
+
+    pub fn manifest(&self) -> String {
+        format!("{}/{}.txt", MANIFEST_DIR, self.name)
+    }
+
+    fn work_dir(&self) -> String {
+        format!("/tmp/crosup-archive-{}", self.name)
+    }
+
+    fn filename(&self) -> String {
+        let path = self.url.split(['?', '#']).next().unwrap_or(&self.url);
+        path.rsplit('/').next().unwrap_or(path).to_string()
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
+    pub fn download_and_extract(&self) -> Result<(), Error> {
+        let filename = self.filename();
+        if !download::is_archive(&filename) {
+            return Err(Error::msg(format!(
+                "{} is not a supported archive (tar.gz, tgz, tar.xz, tar.bz2, tar or zip)",
+                filename
+            )));
+        }
+
+        let work_dir = self.work_dir();
+        let archive = format!("{}/{}", work_dir, filename);
+        let staging = format!("{}/staging", work_dir);
+        let prefix = self.prefix();
+        let manifest = self.manifest();
+
+        download::exec(
+            &format!("rm -rf \"{}\" && mkdir -p \"{}\"", work_dir, work_dir),
+            self.session.clone(),
+        )?;
+        download::download(&self.url, &archive, self.session.clone())?;
+
+        match self.sha256.clone() {
+            Some(checksum) => download::verify_sha256(&archive, &checksum, self.session.clone())?,
+            None => println!(
+                "   {}",
+                format!(
+                    "No checksum provided for {}, skipping verification",
+                    filename
+                )
+                .yellow()
+            ),
+        }
+
+        download::extract(
+            &archive,
+            &staging,
+            self.strip_components,
+            self.session.clone(),
+        )?;
+
+        println!("   Copying files to {}", prefix.bright_green());
+        // directories recorded by a previous install are kept, they already
+        // exist now but weren't there before crosup
+        download::exec(
+            &format!(
+                "mkdir -p \"{manifest_dir}\" && {{ test -f \"{manifest}\" && grep \"^dir \" \"{manifest}\"; {missing}; (cd \"{staging}\" && find . -mindepth 1 -type d) | sed \"s|^\\.|{prefix}|\" | while IFS= read -r dir; do test -d \"$dir\" || echo \"dir $dir\"; done; (cd \"{staging}\" && find . -mindepth 1 ! -type d) | sed \"s|^\\.|{prefix}|\"; }} > \"{manifest}.new\" && mv \"{manifest}.new\" \"{manifest}\" && mkdir -p \"{prefix}\" && cp -R \"{staging}/.\" \"{prefix}\"",
+                manifest_dir = MANIFEST_DIR,
+                manifest = manifest,
+                missing = missing_dirs(&prefix),
+                staging = staging,
+                prefix = prefix,
+            ),
+            self.session.clone(),
+        )?;
+
+        let bin_dir = self.bin_dir();
+        for bin in self.bin.iter() {
+            let filename = bin.rsplit('/').next().unwrap_or(bin);
+            let link = format!("{}/{}", bin_dir, filename);
+            println!("   Linking {} to {}", link.bright_green(), bin);
+            download::exec(
+                &format!(
+                    "{{ {}; }} >> \"{}\" && mkdir -p \"{}\" && ln -sf \"{}/{}\" \"{}\" && echo \"{}\" >> \"{}\"",
+                    missing_dirs(&bin_dir),
+                    manifest,
+                    bin_dir,
+                    prefix,
+                    bin,
+                    link,
+                    link,
+                    manifest
+                ),
+                self.session.clone(),
+            )?;
+        }
+
+        download::exec(&format!("rm -rf \"{}\"", work_dir), self.session.clone())?;
+        Ok(())
+    }
+}
+
+impl Installer for ArchiveInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        println!(
+            "-> 🚚 Installing {} from {}",
+            self.name().bright_green(),
+            self.url
+        );
+
+        self.download_and_extract()?;
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn uninstall(&self) -> Result<(), Error> {
+        let manifest = self.manifest();
+        println!(
+            "-> 🗑️  Removing {} using {}",
+            self.name().bright_green(),
+            manifest
+        );
+        let content = download::exec(&format!("cat \"{}\"", manifest), self.session.clone())
+            .map_err(|_| Error::msg(format!("{} was not installed by crosup", self.name)))?;
+        download::exec(
+            &format!(
+                "{}; rm -f \"{}\"",
+                Manifest::parse(&content).removal_script(),
+                manifest
+            ),
+            self.session.clone(),
+        )?;
+        shell::ShellProfile::detect(self.session.clone())?.remove(&self.name)?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        let command = match (self.version_check.clone(), self.bin.first()) {
+            (Some(command), _) => command,
+            (None, Some(bin)) => bin.rsplit('/').next().unwrap_or(bin).to_string(),
+            (None, None) => {
+                download::exec(
+                    &format!("test -f \"{}\"", self.manifest()),
+                    self.session.clone(),
+                )?;
+                return Ok(true);
+            }
+        };
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let manifest = Manifest {
+            files: vec![
+                "/opt/zig/zig".into(),
+                "/opt/zig/lib/std.zig".into(),
+                "/home/me/.local/bin/zig".into(),
+            ],
+            dirs: vec!["/opt/zig".into(), "/opt/zig/lib".into()],
+        };
+        assert_eq!(Manifest::parse(&manifest.render()), manifest);
+
+        // manifests written before directories were recorded
+        assert_eq!(
+            Manifest::parse("/opt/zig/zig\n\n"),
+            Manifest {
+                files: vec!["/opt/zig/zig".into()],
+                dirs: vec![],
+            }
+        );
+
+        assert_eq!(
+            manifest.removal_script(),
+            "rm -f \"/opt/zig/zig\"; rm -f \"/opt/zig/lib/std.zig\"; rm -f \"/home/me/.local/bin/zig\"; rmdir \"/opt/zig/lib\" 2>/dev/null; rmdir \"/opt/zig\" 2>/dev/null; true"
+        );
+    }
+}
@@ -4,9 +4,12 @@ use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
 use crosup_macros::{brew_install, check_version, exec_bash_with_output};
-use crosup_types::brew::{BrewConfiguration, Package};
+use crosup_types::brew::{BrewConfiguration, Package, Tap};
 
-use super::Installer;
+use super::{download, shell, Installer};
+
+pub const BREW_PATH: &str =
+    "PATH=/home/linuxbrew/.linuxbrew/bin:/opt/homebrew/bin:/usr/local/bin:$PATH";
 
 #[derive(Default, Clone)]
 pub struct BrewInstaller {
@@ -17,10 +20,14 @@ pub struct BrewInstaller {
     pub pkgs: Vec<String>,
     pub preinstall: Option<String>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub provider: String,
     pub session: Option<Session>,
     pub cask: bool,
+    pub args: Vec<String>,
+    pub head: bool,
+    pub pin: bool,
 }
 
 impl From<BrewConfiguration> for BrewInstaller {
@@ -35,17 +42,32 @@ impl From<BrewConfiguration> for BrewInstaller {
     }
 }
 
+/// Tap of a fully qualified formula name, e.g. `hashicorp/tap` for
+/// `hashicorp/tap/terraform`.
+fn tap_of(name: &str) -> Option<String> {
+    match name.split('/').collect::<Vec<_>>()[..] {
+        [user, repo, _] => Some(format!("{}/{}", user, repo)),
+        _ => None,
+    }
+}
+
 impl From<Package> for BrewInstaller {
     fn from(pkg: Package) -> Self {
+        let mut dependencies = vec!["homebrew".to_string()];
+        dependencies.extend(pkg.tap.or(tap_of(&pkg.name)));
         Self {
             name: pkg.name,
             version: "latest".to_string(),
-            dependencies: vec!["homebrew".into()],
+            dependencies,
             preinstall: pkg.preinstall,
             postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
             provider: "brew".into(),
             version_check: pkg.version_check,
             cask: pkg.cask.unwrap_or(false),
+            args: pkg.args.unwrap_or_default(),
+            head: pkg.head.unwrap_or(false),
+            pin: pkg.pin.unwrap_or(false),
             ..Default::default()
         }
     }
@@ -74,6 +96,22 @@ impl BrewInstaller {
         }
         Ok(())
     }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
+    fn pin(&self) -> Result<(), Error> {
+        if !self.pin || self.cask {
+            return Ok(());
+        }
+        download::exec(
+            &format!("{} brew pin {}", BREW_PATH, self.name),
+            self.session.clone(),
+        )
+        .map_err(|_| Error::msg(format!("Failed to pin {}", self.name)))?;
+        Ok(())
+    }
 }
 
 impl Installer for BrewInstaller {
@@ -83,12 +121,20 @@ impl Installer for BrewInstaller {
                 "-> {} is already installed, skipping",
                 self.name().bright_green()
             );
+            self.pin()?;
+            self.shell_init()?;
             return Ok(());
         }
         println!("-> 🚚 Installing {}", self.name().bright_green());
         self.preinstall()?;
-        brew_install!(self, &self.name, self.cask, self.session.clone());
+        let mut args = self.args.clone();
+        if self.head {
+            args.push("--HEAD".into());
+        }
+        brew_install!(self, &self.name, self.cask, &args, self.session.clone());
+        self.pin()?;
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
@@ -101,7 +147,8 @@ impl Installer for BrewInstaller {
             check_version!(self, command, self.session.clone());
             return Ok(false);
         }
-        let command = self.name.clone();
+        // formulae from a tap are installed as the last part of their name
+        let command = self.name.rsplit('/').next().unwrap_or_default();
         check_version!(self, command, self.session.clone());
         Ok(false)
     }
@@ -130,3 +177,122 @@ impl Installer for BrewInstaller {
         self
     }
 }
+
+#[derive(Default, Clone)]
+pub struct BrewTapInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub url: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Tap> for BrewTapInstaller {
+    fn from(tap: Tap) -> Self {
+        Self {
+            name: tap.name,
+            version: "latest".into(),
+            dependencies: vec!["homebrew".into()],
+            url: tap.url,
+            provider: "brew-tap".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Installer for BrewTapInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already tapped, skipping",
+                self.name().bright_green()
+            );
+            return Ok(());
+        }
+        println!("-> 🚚 Tapping {}", self.name().bright_green());
+        let command = format!(
+            "{} brew tap {} {}",
+            BREW_PATH,
+            self.name,
+            self.url.clone().unwrap_or_default()
+        );
+        download::exec(command.trim(), self.session.clone())
+            .map_err(|_| Error::msg(format!("Failed to tap {}", self.name)))?;
+        Ok(())
+    }
+
+    fn uninstall(&self) -> Result<(), Error> {
+        println!("-> 🗑️  Untapping {}", self.name().bright_green());
+        download::exec(
+            &format!("{} brew untap {}", BREW_PATH, self.name),
+            self.session.clone(),
+        )
+        .map_err(|_| Error::msg(format!("Failed to untap {}", self.name)))?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already tapped",
+            self.name.bright_green()
+        );
+        download::capture(
+            &format!("{} brew tap | grep -qix {}", BREW_PATH, self.name),
+            self.session.clone(),
+        )
+        .map_err(|_| Error::msg(format!("{} is not tapped", self.name)))?;
+        Ok(true)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_dependency() {
+        let installer = BrewInstaller::from(Package {
+            name: "hashicorp/tap/terraform".into(),
+            ..Default::default()
+        });
+        assert_eq!(installer.dependencies, vec!["homebrew", "hashicorp/tap"]);
+
+        let installer = BrewInstaller::from(Package {
+            name: "terraform".into(),
+            tap: Some("hashicorp/tap".into()),
+            ..Default::default()
+        });
+        assert_eq!(installer.dependencies, vec!["homebrew", "hashicorp/tap"]);
+
+        let installer = BrewInstaller::from(Package {
+            name: "bat".into(),
+            ..Default::default()
+        });
+        assert_eq!(installer.dependencies, vec!["homebrew"]);
+    }
+}
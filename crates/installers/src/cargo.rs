@@ -0,0 +1,180 @@
+use anyhow::Error;
+use crosup_macros::{cargo_install, check_version, exec_bash_with_output};
+use crosup_types::cargo::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct CargoInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub features: Option<Vec<String>>,
+    pub all_features: Option<bool>,
+    pub no_default_features: Option<bool>,
+    pub git: Option<String>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub rev: Option<String>,
+    pub locked: Option<bool>,
+    pub bins: Option<Vec<String>>,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for CargoInstaller {
+    fn from(pkg: Package) -> Self {
+        let mut dependencies = vec!["rustup".into()];
+        dependencies.extend(pkg.depends_on.unwrap_or(vec![]));
+        Self {
+            name: pkg.name,
+            version: pkg.version.unwrap_or("latest".into()),
+            dependencies,
+            features: pkg.features,
+            all_features: pkg.all_features,
+            no_default_features: pkg.no_default_features,
+            git: pkg.git,
+            branch: pkg.branch,
+            tag: pkg.tag,
+            rev: pkg.rev,
+            locked: pkg.locked,
+            bins: pkg.bins,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "cargo".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl CargoInstaller {
+    pub fn options(&self) -> String {
+        let mut options = vec![];
+
+        if let Some(git) = self.git.clone() {
+            options.push(format!("--git {}", git));
+            if let Some(branch) = self.branch.clone() {
+                options.push(format!("--branch {}", branch));
+            }
+            if let Some(tag) = self.tag.clone() {
+                options.push(format!("--tag {}", tag));
+            }
+            if let Some(rev) = self.rev.clone() {
+                options.push(format!("--rev {}", rev));
+            }
+        }
+
+        if self.version != "latest" && self.git.is_none() {
+            options.push(format!("--version {}", self.version));
+        }
+
+        if let Some(features) = self.features.clone() {
+            options.push(format!("--features {}", features.join(",")));
+        }
+
+        if self.all_features.unwrap_or(false) {
+            options.push("--all-features".into());
+        }
+
+        if self.no_default_features.unwrap_or(false) {
+            options.push("--no-default-features".into());
+        }
+
+        if self.locked.unwrap_or(false) {
+            options.push("--locked".into());
+        }
+
+        if let Some(bins) = self.bins.clone() {
+            for bin in bins {
+                options.push(format!("--bin {}", bin));
+            }
+        }
+
+        options.join(" ")
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+}
+
+impl Installer for CargoInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        println!("-> 🚚 Installing {}", self.name().bright_green());
+
+        let options = self.options();
+        let command = format!("cargo install {} {}", options, self.name);
+        println!("-> Running {}", command.bright_green());
+        cargo_install!(self.name, options, self.session.clone());
+
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        if let Some(command) = self.version_check.clone() {
+            check_version!(self, command, self.session.clone());
+            return Ok(false);
+        }
+        let command = self.name.clone();
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
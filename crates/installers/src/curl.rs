@@ -7,7 +7,7 @@ use std::{any::Any, io::BufRead, process::Stdio};
 use crosup_macros::{check_version, exec_bash_with_output};
 use crosup_types::curl::Script;
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct CurlInstaller {
@@ -18,6 +18,7 @@ pub struct CurlInstaller {
     pub enable_sudo: Option<bool>,
     pub version_check: Option<String>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub args: Option<String>,
     pub env: Option<IndexMap<String, String>>,
     pub shell: String,
@@ -35,6 +36,7 @@ impl From<Script> for CurlInstaller {
             enable_sudo: config.enable_sudo,
             version_check: config.version_check,
             postinstall: config.postinstall,
+            shell_init: config.shell_init.unwrap_or_default(),
             args: config.args,
             env: config.env,
             shell: config.shell.unwrap_or("sh".into()),
@@ -57,6 +59,10 @@ impl CurlInstaller {
         }
         Ok(())
     }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
 }
 
 impl Installer for CurlInstaller {
@@ -66,6 +72,7 @@ impl Installer for CurlInstaller {
                 "-> {} is already installed, skipping",
                 self.name().bright_green()
             );
+            self.shell_init()?;
             return Ok(());
         }
 
@@ -93,6 +100,7 @@ impl Installer for CurlInstaller {
             let session = self.session.as_ref().unwrap();
             crosup_ssh::exec(session.clone(), &script)?;
             self.postinstall()?;
+            self.shell_init()?;
             return Ok(());
         }
 
@@ -118,6 +126,7 @@ impl Installer for CurlInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
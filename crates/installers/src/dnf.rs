@@ -5,7 +5,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct DnfInstaller {
@@ -15,6 +15,7 @@ pub struct DnfInstaller {
     pub dnf_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub provider: String,
     pub session: Option<Session>,
@@ -27,6 +28,7 @@ impl From<Package> for DnfInstaller {
             packages: pkg.packages,
             dnf_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "dnf".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             ..Default::default()
         }
@@ -34,6 +36,10 @@ impl From<Package> for DnfInstaller {
 }
 
 impl DnfInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.dnf_dependencies.is_empty() {
             return Ok(());
@@ -70,6 +76,7 @@ impl Installer for DnfInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -84,6 +91,7 @@ impl Installer for DnfInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
@@ -0,0 +1,224 @@
+use anyhow::Error;
+use crosup_macros::exec_bash_capture;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::process::Stdio;
+
+pub use crosup_macros::{take_journal, Journal};
+
+/// Runs a command and records it in the journal with its exit status.
+pub fn exec(command: &str, session: Option<Session>) -> Result<String, Error> {
+    let (status, output) = match session {
+        Some(session) => {
+            let remote = format!("bash -c '{}'", command);
+            let (status, output) = crosup_ssh::run_with_output(session, &remote)?;
+            (Some(status), output)
+        }
+        None => {
+            let output = std::process::Command::new("bash")
+                .arg("-c")
+                .arg(command)
+                .stdout(Stdio::piped())
+                .output()?;
+            (
+                output.status.code(),
+                String::from_utf8_lossy(&output.stdout).to_string(),
+            )
+        }
+    };
+    crosup_macros::journal(command, &output, status);
+    match status {
+        Some(0) => Ok(output),
+        _ => Err(Error::msg(format!("Failed to execute {}", command))),
+    }
+}
+
+/// Runs a command without journaling it, for the commands checking the
+/// target machine rather than changing it.
+pub fn capture(command: &str, session: Option<Session>) -> Result<String, Error> {
+    Ok(exec_bash_capture!(command, session))
+}
+
+/// Fails with a clear message when none of the commands a tool is installed
+/// with is available, e.g. `go` which crosup doesn't install by default.
+/// `path` is prepended to the PATH, like the install commands do.
+pub fn require(
+    commands: &[&str],
+    path: &str,
+    dependency: &str,
+    tool: &str,
+    session: Option<Session>,
+) -> Result<(), Error> {
+    let command = format!(
+        "PATH={}:$PATH; {}",
+        path,
+        commands
+            .iter()
+            .map(|x| format!("command -v {} > /dev/null", x))
+            .collect::<Vec<_>>()
+            .join(" || ")
+    );
+    capture(&command, session).map_err(|_| {
+        Error::msg(format!(
+            "Missing dependency: {} is required to install {}, install it first or add a tool named {} to the configuration",
+            dependency, tool, dependency
+        ))
+    })?;
+    Ok(())
+}
+
+/// Returns the operating system and architecture of the target machine,
+/// normalized to the names most release assets use (`linux`/`darwin`,
+/// `x86_64`/`aarch64`).
+pub fn platform(session: Option<Session>) -> Result<(String, String), Error> {
+    let os = capture("uname -s", session.clone())?.trim().to_lowercase();
+    let arch = capture("uname -m", session)?.trim().to_lowercase();
+    let arch = match arch.as_str() {
+        "arm64" => "aarch64".to_string(),
+        "amd64" => "x86_64".to_string(),
+        _ => arch,
+    };
+    Ok((os, arch))
+}
+
+pub fn download(url: &str, dest: &str, session: Option<Session>) -> Result<(), Error> {
+    println!("   Downloading {}", url.bright_green());
+    let command = format!(
+        "mkdir -p \"$(dirname \"{}\")\" && curl -fsSL -o \"{}\" \"{}\"",
+        dest, dest, url
+    );
+    exec(&command, session).map_err(|_| Error::msg(format!("Failed to download {}", url)))?;
+    Ok(())
+}
+
+pub fn sha256(path: &str, session: Option<Session>) -> Result<String, Error> {
+    let command = format!(
+        "(sha256sum \"{}\" 2>/dev/null || shasum -a 256 \"{}\") | cut -d \" \" -f 1",
+        path, path
+    );
+    Ok(exec(&command, session)?.trim().to_string())
+}
+
+pub fn verify_sha256(path: &str, expected: &str, session: Option<Session>) -> Result<(), Error> {
+    let actual = sha256(path, session)?;
+    if actual != expected.trim().to_lowercase() {
+        return Err(Error::msg(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            path, expected, actual
+        )));
+    }
+    println!("   {} {}", "sha256 OK".cyan(), actual.cyan());
+    Ok(())
+}
+
+/// Looks up the checksum of `asset` in the content of a checksums file
+/// (`<sha256>  <filename>` per line, as produced by `sha256sum`).
+pub fn find_checksum(checksums: &str, asset: &str) -> Option<String> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        let filename = parts.next()?.trim_start_matches('*');
+        match filename == asset || filename.ends_with(&format!("/{}", asset)) {
+            true => Some(checksum.to_lowercase()),
+            false => None,
+        }
+    })
+}
+
+pub fn is_archive(path: &str) -> bool {
+    [".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".tar", ".zip"]
+        .iter()
+        .any(|ext| path.ends_with(ext))
+}
+
+pub fn extract(
+    archive: &str,
+    dest: &str,
+    strip_components: u32,
+    session: Option<Session>,
+) -> Result<(), Error> {
+    println!("   Extracting {} into {}", archive.bright_green(), dest);
+    let command = match archive.ends_with(".zip") {
+        true if strip_components > 0 => {
+            return Err(Error::msg(
+                "strip_components is not supported for zip archives",
+            ))
+        }
+        true => format!(
+            "mkdir -p \"{}\" && unzip -o -q \"{}\" -d \"{}\"",
+            dest, archive, dest
+        ),
+        false => format!(
+            "mkdir -p \"{}\" && tar -xf \"{}\" -C \"{}\" --strip-components={}",
+            dest, archive, dest, strip_components
+        ),
+    };
+    exec(&command, session).map_err(|_| Error::msg(format!("Failed to extract {}", archive)))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crosup_macros::{exec_bash_with_output, npm_install};
+    use std::io::BufRead;
+
+    use super::*;
+
+    #[test]
+    fn test_journal() {
+        take_journal();
+        assert_eq!(take_journal(), None);
+
+        exec("echo hello", None).unwrap();
+        exec("true", None).unwrap();
+        exec("printf world", None).unwrap();
+        assert_eq!(
+            take_journal(),
+            Some(Journal {
+                command: "echo hello\ntrue\nprintf world".into(),
+                output: Some("hello\nworld".into()),
+                exit_status: Some(0),
+            })
+        );
+        assert_eq!(take_journal(), None);
+
+        assert!(exec("exit 3", None).is_err());
+        let journal = take_journal().unwrap();
+        assert_eq!(journal.command, "exit 3");
+        assert_eq!(journal.output, None);
+        assert_eq!(journal.exit_status, Some(3));
+
+        // install commands run by the macros are journaled too
+        let install = || -> Result<(), Error> {
+            exec_bash_with_output!("echo building", None::<Session>);
+            npm_install!("echo installing; exit 3", "pkg", None::<Session>);
+            Ok(())
+        };
+        assert!(install().is_err());
+        let journal = take_journal().unwrap();
+        assert_eq!(journal.command, "echo building\necho installing; exit 3");
+        assert_eq!(journal.output.as_deref(), Some("building\ninstalling\n"));
+        assert_eq!(journal.exit_status, Some(3));
+
+        // commands checking the machine aren't journaled
+        require(&["sh"], "/nonexistent", "sh", "tool", None).unwrap();
+        platform(None).unwrap();
+        assert_eq!(take_journal(), None);
+    }
+
+    #[test]
+    fn test_require() {
+        assert!(require(
+            &["missing-command", "sh"],
+            "/nonexistent",
+            "sh",
+            "tool",
+            None
+        )
+        .is_ok());
+        let error = require(&["missing-command"], "/nonexistent", "go", "gopls", None)
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Missing dependency: go is required to install gopls"));
+    }
+}
@@ -5,7 +5,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct EmergeInstaller {
@@ -15,6 +15,7 @@ pub struct EmergeInstaller {
     pub emerge_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub ask: bool,
     pub verbose: bool,
@@ -29,6 +30,7 @@ impl From<Package> for EmergeInstaller {
             packages: pkg.packages,
             emerge_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "emerge".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             ask: pkg.ask.unwrap_or(false),
             verbose: pkg.verbose.unwrap_or(false),
@@ -38,6 +40,10 @@ impl From<Package> for EmergeInstaller {
 }
 
 impl EmergeInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.emerge_dependencies.is_empty() {
             return Ok(());
@@ -74,6 +80,7 @@ impl Installer for EmergeInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -96,6 +103,7 @@ impl Installer for EmergeInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
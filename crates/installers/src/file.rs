@@ -0,0 +1,373 @@
+use anyhow::Error;
+use crosup_types::file::Dotfile;
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{
+    any::Any,
+    os::unix::fs::PermissionsExt,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{download, Installer};
+
+/// Where linked dotfiles are uploaded on remote machines, the target is then
+/// symlinked to this copy.
+pub const REMOTE_DOTFILES_DIR: &str = ".local/share/crosup/dotfiles";
+
+#[derive(Default, Clone)]
+pub struct FileInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub source: String,
+    pub target: String,
+    pub mode: String,
+    pub template: bool,
+    pub vars: IndexMap<String, String>,
+    pub backup: bool,
+    pub permissions: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Dotfile> for FileInstaller {
+    fn from(dotfile: Dotfile) -> Self {
+        Self {
+            name: dotfile.name,
+            version: "latest".into(),
+            dependencies: dotfile.depends_on.unwrap_or(vec![]),
+            source: dotfile.source,
+            target: dotfile.target,
+            mode: dotfile.mode.unwrap_or("link".into()),
+            template: dotfile.template.unwrap_or(false),
+            vars: dotfile.vars.unwrap_or_default(),
+            backup: dotfile.backup.unwrap_or(true),
+            permissions: dotfile.permissions,
+            provider: "file".into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// State of a dotfile target on disk.
+#[derive(Debug, PartialEq)]
+pub enum State {
+    Missing,
+    Link(String),
+    File(Vec<u8>),
+}
+
+/// Replaces the `{{ name }}` placeholders of a template with the given
+/// variables, unknown variables are an error.
+pub fn render(template: &str, vars: &IndexMap<String, String>) -> Result<String, Error> {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        let key = rest[start + 2..end].trim();
+        let value = vars
+            .get(key)
+            .ok_or_else(|| Error::msg(format!("Unknown template variable: {}", key)))?;
+        output.push_str(&rest[..start]);
+        output.push_str(value);
+        rest = &rest[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+impl FileInstaller {
+    fn exec(&self, command: &str) -> Result<String, Error> {
+        download::exec(command, self.session.clone())
+    }
+
+    /// Runs a command checking the target machine, which isn't journaled.
+    fn capture(&self, command: &str) -> Result<String, Error> {
+        download::capture(command, self.session.clone())
+    }
+
+    fn home(&self) -> Result<String, Error> {
+        match self.session {
+            Some(_) => Ok(self.capture("echo $HOME")?.trim().to_string()),
+            None => Ok(std::env::var("HOME")?),
+        }
+    }
+
+    pub fn target_path(&self) -> Result<String, Error> {
+        match self.target.strip_prefix("~/") {
+            Some(path) => Ok(format!("{}/{}", self.home()?, path)),
+            None => Ok(self.target.clone()),
+        }
+    }
+
+    fn source_path(&self) -> Result<String, Error> {
+        let path = Path::new(&self.source);
+        if !path.exists() {
+            return Err(Error::msg(format!(
+                "Source file {} for {} not found",
+                self.source, self.name
+            )));
+        }
+        Ok(path.canonicalize()?.to_string_lossy().to_string())
+    }
+
+    fn mode(&self) -> Result<u32, Error> {
+        match self.permissions.clone() {
+            Some(permissions) => u32::from_str_radix(permissions.trim_start_matches("0o"), 8)
+                .map_err(|_| Error::msg(format!("Invalid permissions: {}", permissions))),
+            None => Ok(0o644),
+        }
+    }
+
+    /// Host variables available in templates, merged with the `vars` block.
+    pub fn variables(&self) -> Result<IndexMap<String, String>, Error> {
+        let (os, arch) = download::platform(self.session.clone())?;
+        let mut vars = IndexMap::new();
+        vars.insert(
+            "hostname".into(),
+            self.capture("hostname")?.trim().to_string(),
+        );
+        vars.insert("user".into(), self.capture("id -un")?.trim().to_string());
+        vars.insert("home".into(), self.home()?);
+        vars.insert("os".into(), os);
+        vars.insert("arch".into(), arch);
+        vars.extend(self.vars.clone());
+        Ok(vars)
+    }
+
+    fn is_link(&self) -> bool {
+        self.mode == "link" && !self.template
+    }
+
+    fn content(&self) -> Result<Vec<u8>, Error> {
+        let content = std::fs::read(self.source_path()?)?;
+        if !self.template {
+            return Ok(content);
+        }
+        let template = String::from_utf8(content)?;
+        Ok(render(&template, &self.variables()?)?.into_bytes())
+    }
+
+    /// Path the target should link to: the source itself locally, an
+    /// uploaded copy of it on remote machines.
+    fn link_source(&self) -> Result<String, Error> {
+        match self.session {
+            Some(_) => Ok(format!(
+                "{}/{}/{}",
+                self.home()?,
+                REMOTE_DOTFILES_DIR,
+                self.name
+            )),
+            None => self.source_path(),
+        }
+    }
+
+    pub fn desired(&self) -> Result<State, Error> {
+        match self.is_link() {
+            true => Ok(State::Link(self.link_source()?)),
+            false => Ok(State::File(self.content()?)),
+        }
+    }
+
+    pub fn current(&self) -> Result<State, Error> {
+        let target = self.target_path()?;
+        match self.session.clone() {
+            Some(session) => {
+                if let Some(link) = crosup_ssh::read_link(session.clone(), &target)? {
+                    return Ok(State::Link(link));
+                }
+                match crosup_ssh::read_file(session, &target)? {
+                    Some(content) => Ok(State::File(content)),
+                    None => Ok(State::Missing),
+                }
+            }
+            None => {
+                let path = Path::new(&target);
+                if path.is_symlink() {
+                    let link = std::fs::read_link(path)?;
+                    return Ok(State::Link(link.to_string_lossy().to_string()));
+                }
+                match path.exists() {
+                    true => Ok(State::File(std::fs::read(path)?)),
+                    false => Ok(State::Missing),
+                }
+            }
+        }
+    }
+
+    /// Describes how the target differs from the source, `None` when it is
+    /// up to date.
+    pub fn drift(&self) -> Result<Option<String>, Error> {
+        let desired = self.desired()?;
+        let current = self.current()?;
+        if desired == current {
+            if let (State::Link(_), Some(session)) = (&desired, self.session.clone()) {
+                let uploaded = crosup_ssh::read_file(session, &self.link_source()?)?;
+                if uploaded != Some(std::fs::read(self.source_path()?)?) {
+                    return Ok(Some(format!("{} has changed", self.source)));
+                }
+            }
+            return Ok(None);
+        }
+        let target = self.target.clone();
+        let drift = match (desired, current) {
+            (_, State::Missing) => format!("{} does not exist", target),
+            (State::Link(source), State::Link(link)) => {
+                format!("{} links to {} instead of {}", target, link, source)
+            }
+            (State::Link(source), State::File(_)) => {
+                format!(
+                    "{} is a regular file, expected a link to {}",
+                    target, source
+                )
+            }
+            (State::File(_), State::Link(link)) => {
+                format!("{} is a link to {}, expected a regular file", target, link)
+            }
+            (State::File(_), State::File(_)) => {
+                format!("{} has been modified", target)
+            }
+            (State::Missing, _) => unreachable!(),
+        };
+        Ok(Some(drift))
+    }
+
+    fn backup(&self, target: &str) -> Result<(), Error> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let backup = format!("{}.crosup-{}.bak", target, timestamp);
+        println!("   Backing up {} to {}", target, backup.bright_green());
+        self.exec(&format!("mv \"{}\" \"{}\"", target, backup))?;
+        Ok(())
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), Error> {
+        let mode = self.mode()?;
+        match self.session.clone() {
+            Some(session) => crosup_ssh::write_file(session, path, content, mode as i32),
+            None => {
+                std::fs::write(path, content)?;
+                std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+                Ok(())
+            }
+        }
+    }
+
+    fn apply(&self) -> Result<(), Error> {
+        let target = self.target_path()?;
+        self.exec(&format!("mkdir -p \"$(dirname \"{}\")\"", target))?;
+
+        match self.current()? {
+            State::Missing => {}
+            _ if self.backup => self.backup(&target)?,
+            _ => {
+                self.exec(&format!("rm -f \"{}\"", target))?;
+            }
+        }
+
+        match self.desired()? {
+            State::Link(source) => {
+                if self.session.is_some() {
+                    self.exec(&format!("mkdir -p \"$(dirname \"{}\")\"", source))?;
+                    self.write(&source, &std::fs::read(self.source_path()?)?)?;
+                }
+                println!("   Linking {} to {}", target.bright_green(), source);
+                self.exec(&format!("ln -sfn \"{}\" \"{}\"", source, target))?;
+            }
+            State::File(content) => {
+                println!("   Writing {}", target.bright_green());
+                self.write(&target, &content)?;
+            }
+            State::Missing => {}
+        }
+        Ok(())
+    }
+}
+
+impl Installer for FileInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!("-> {} is up to date, skipping", self.name().bright_green());
+            return Ok(());
+        }
+        println!(
+            "-> 📄 Installing {} to {}",
+            self.name().bright_green(),
+            self.target
+        );
+        self.apply()
+    }
+
+    fn uninstall(&self) -> Result<(), Error> {
+        if let Some(drift) = self.drift()? {
+            return Err(Error::msg(format!(
+                "Not removing {}: {}",
+                self.target, drift
+            )));
+        }
+        let target = self.target_path()?;
+        println!("-> 🗑️  Removing {}", target.bright_green());
+        self.exec(&format!("rm -f \"{}\"", target))?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!("-> Checking if {} is up to date", self.name.bright_green());
+        match self.drift()? {
+            Some(drift) => {
+                println!("   {}", drift.yellow());
+                Err(Error::msg(drift))
+            }
+            None => Ok(true),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let vars: IndexMap<String, String> = [
+            ("hostname".into(), "devbox".into()),
+            ("user".into(), "tsiry".into()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            render("export HOST={{ hostname }}\nUSER={{user}}\n", &vars).unwrap(),
+            "export HOST=devbox\nUSER=tsiry\n"
+        );
+        assert_eq!(render("no placeholders", &vars).unwrap(), "no placeholders");
+        assert!(render("{{ missing }}", &vars).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::Error;
+use crosup_types::flatpak::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::any::Any;
+
+use super::{download, Installer};
+
+pub const FLATHUB_URL: &str = "https://dl.flathub.org/repo/flathub.flatpakrepo";
+
+#[derive(Default, Clone)]
+pub struct FlatpakInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub app_id: String,
+    pub remote: String,
+    pub user: bool,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for FlatpakInstaller {
+    fn from(pkg: Package) -> Self {
+        Self {
+            app_id: pkg.app_id.unwrap_or(pkg.name.clone()),
+            name: pkg.name,
+            version: "latest".into(),
+            dependencies: pkg.depends_on.unwrap_or(vec![]),
+            remote: pkg.remote.unwrap_or("flathub".into()),
+            user: pkg.user.unwrap_or(false),
+            provider: "flatpak".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl FlatpakInstaller {
+    fn exec(&self, command: &str) -> Result<String, Error> {
+        download::exec(command, self.session.clone())
+    }
+
+    fn flatpak(&self) -> &str {
+        match self.user {
+            true => "flatpak --user",
+            false => "sudo flatpak --system",
+        }
+    }
+
+    fn setup_remote(&self) -> Result<(), Error> {
+        if self.remote != "flathub" {
+            return Ok(());
+        }
+        self.exec(&format!(
+            "{} remote-add --if-not-exists flathub {}",
+            self.flatpak(),
+            FLATHUB_URL
+        ))?;
+        Ok(())
+    }
+}
+
+impl Installer for FlatpakInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            return Ok(());
+        }
+
+        println!("-> 🚚 Installing {}", self.name().bright_green());
+        self.setup_remote()?;
+
+        let command = format!(
+            "{} install -y --noninteractive {} {}",
+            self.flatpak(),
+            self.remote,
+            self.app_id
+        );
+        println!("   Running {}", command.bright_green());
+        self.exec(&command)
+            .map_err(|_| Error::msg(format!("Failed to install {}", self.app_id)))?;
+        Ok(())
+    }
+
+    fn uninstall(&self) -> Result<(), Error> {
+        println!("-> 🗑️  Removing {}", self.name().bright_green());
+        self.exec(&format!(
+            "{} uninstall -y --noninteractive {}",
+            self.flatpak(),
+            self.app_id
+        ))
+        .map_err(|_| Error::msg(format!("Failed to uninstall {}", self.app_id)))?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        let installation = match self.user {
+            true => "--user",
+            false => "--system",
+        };
+        download::capture(
+            &format!(
+                "flatpak info {} {} > /dev/null 2>&1",
+                installation, self.app_id
+            ),
+            self.session.clone(),
+        )
+        .map_err(|_| Error::msg(format!("{} is not installed", self.app_id)))?;
+        Ok(true)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -6,7 +6,7 @@ use std::{any::Any, io::BufRead, path::Path, process::Stdio};
 use crosup_macros::exec_bash_with_output;
 use crosup_types::git::Repository;
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct GitInstaller {
@@ -17,6 +17,7 @@ pub struct GitInstaller {
     pub install: String,
     pub preinstall: Option<String>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub install_check: Option<String>,
     pub recursive: Option<bool>,
     pub depth: Option<u32>,
@@ -35,6 +36,7 @@ impl From<Repository> for GitInstaller {
             install: config.install,
             preinstall: config.preinstall,
             postinstall: config.postinstall,
+            shell_init: config.shell_init.unwrap_or_default(),
             install_check: config.install_check,
             recursive: config.recursive,
             depth: config.depth,
@@ -68,6 +70,10 @@ impl GitInstaller {
         }
         Ok(())
     }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
 }
 
 impl Installer for GitInstaller {
@@ -77,6 +83,7 @@ impl Installer for GitInstaller {
                 "-> {} is already installed, skipping",
                 self.name().bright_green()
             );
+            self.shell_init()?;
             return Ok(());
         }
         println!("-> 🚚 Installing {}", self.name().bright_green());
@@ -145,6 +152,7 @@ impl Installer for GitInstaller {
         exec_bash_with_output!(self.install.clone(), self.session.clone());
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
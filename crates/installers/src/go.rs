@@ -0,0 +1,128 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_bash_with_output, go_install};
+use crosup_types::go::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{download, shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct GoInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub package: String,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for GoInstaller {
+    fn from(pkg: Package) -> Self {
+        let mut dependencies = vec!["go".into()];
+        dependencies.extend(pkg.depends_on.unwrap_or(vec![]));
+        Self {
+            name: pkg.name,
+            version: pkg.version.unwrap_or("latest".into()),
+            dependencies,
+            package: pkg.package,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "go".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl GoInstaller {
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+}
+
+impl Installer for GoInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        println!("-> 🚚 Installing {}", self.name().bright_green());
+        download::require(
+            &["go"],
+            "/usr/local/go/bin",
+            "go",
+            &self.name,
+            self.session.clone(),
+        )?;
+
+        let package = format!("{}@{}", self.package, self.version);
+        println!(
+            "-> Running {}",
+            format!("go install {}", package).bright_green()
+        );
+        go_install!(package, "", self.session.clone());
+
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        if let Some(command) = self.version_check.clone() {
+            check_version!(self, command, self.session.clone());
+            return Ok(false);
+        }
+        let command = self.name.clone();
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -4,21 +4,42 @@ use anyhow::Error;
 
 pub mod apk;
 pub mod apt;
+pub mod archive;
 pub mod brew;
+pub mod cargo;
 pub mod curl;
 pub mod dnf;
+pub mod download;
 pub mod emerge;
+pub mod file;
+pub mod flatpak;
 pub mod fleek;
 pub mod git;
+pub mod go;
 pub mod home_manager;
 pub mod nix;
+pub mod nix_conf;
+pub mod npm;
 pub mod pacman;
+pub mod pipx;
+pub mod release;
+pub mod rpm_ostree;
+pub mod runtime;
+pub mod service;
+pub mod shell;
 pub mod slackpkg;
+pub mod xbps;
 pub mod yum;
 pub mod zypper;
 
 pub trait Installer {
     fn install(&self) -> Result<(), Error>;
+    fn uninstall(&self) -> Result<(), Error> {
+        Err(Error::msg(format!(
+            "{} installer does not support uninstall",
+            self.provider()
+        )))
+    }
     fn is_installed(&self) -> Result<bool, Error>;
     fn name(&self) -> &str;
     fn version(&self) -> &str;
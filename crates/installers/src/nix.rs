@@ -4,9 +4,9 @@ use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
 use crosup_macros::{check_version, exec_bash_with_output};
-use crosup_types::nix::Package;
+use crosup_types::nix::{NixConf, Package};
 
-use super::Installer;
+use super::{nix_conf, shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct NixInstaller {
@@ -18,6 +18,8 @@ pub struct NixInstaller {
     pub accept_flake_config: Option<bool>,
     pub preinstall: Option<String>,
     pub flake: String,
+    pub nix_conf: Option<NixConf>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub provider: String,
     pub session: Option<Session>,
@@ -34,7 +36,9 @@ impl From<Package> for NixInstaller {
             accept_flake_config: pkg.accept_flake_config,
             preinstall: pkg.preinstall,
             flake: pkg.flake,
+            nix_conf: pkg.nix_conf,
             dependencies,
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             provider: "nix".into(),
             ..Default::default()
@@ -43,6 +47,17 @@ impl From<Package> for NixInstaller {
 }
 
 impl NixInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
+    fn nix_conf(&self) -> Result<(), Error> {
+        if let Some(conf) = self.nix_conf.as_ref() {
+            nix_conf::apply(conf, self.session.clone())?;
+        }
+        Ok(())
+    }
+
     fn preinstall(&self) -> Result<(), Error> {
         if let Some(command) = self.preinstall.clone() {
             println!("-> Running preinstall command:\n{}", command.bright_green());
@@ -67,9 +82,12 @@ impl Installer for NixInstaller {
                 "-> {} is already installed, skipping",
                 self.name().bright_green()
             );
+            self.nix_conf()?;
+            self.shell_init()?;
             return Ok(());
         }
         println!("-> 🚚 Installing {}", self.name().bright_green());
+        self.nix_conf()?;
         self.preinstall()?;
 
         let impure = match self.impure {
@@ -101,6 +119,7 @@ impl Installer for NixInstaller {
         );
         exec_bash_with_output!(command, self.session.clone());
 
+        self.shell_init()?;
         Ok(())
     }
 
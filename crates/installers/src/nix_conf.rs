@@ -0,0 +1,199 @@
+use anyhow::Error;
+use crosup_types::nix::NixConf;
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::download;
+
+pub const NIX_CONF: &str = "/etc/nix/nix.conf";
+
+/// A nix.conf setting: list settings are added to the current values,
+/// other settings replace them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Setting {
+    List(Vec<String>),
+    Value(String),
+}
+
+/// Flattens a `nix_conf` block into nix.conf keys.
+pub fn settings(conf: &NixConf, user: &str) -> IndexMap<String, Setting> {
+    let lists = [
+        ("substituters", &conf.substituters),
+        ("trusted-substituters", &conf.trusted_substituters),
+        ("trusted-public-keys", &conf.trusted_public_keys),
+        ("trusted-users", &conf.trusted_users),
+        ("experimental-features", &conf.experimental_features),
+    ];
+    let mut settings = IndexMap::new();
+    for (key, values) in lists {
+        if let Some(values) = values {
+            let values = values.iter().map(|x| x.replace("$USER", user)).collect();
+            settings.insert(key.to_string(), Setting::List(values));
+        }
+    }
+    for (key, value) in &conf.settings {
+        settings.insert(key.replace('_', "-"), Setting::Value(value.clone()));
+    }
+    settings
+}
+
+fn parse(line: &str) -> Option<(&str, &str)> {
+    let line = line.split('#').next()?;
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+/// Merges `settings` into the content of a nix.conf. Values of a list setting
+/// are added to its line, or to its `extra-` line when the setting isn't set
+/// so that the defaults of nix are kept. Duplicate lines of a setting are
+/// folded into the first one.
+pub fn merge(content: &str, settings: &IndexMap<String, Setting>) -> String {
+    // resolve the key each setting is written to
+    let keys: IndexMap<String, String> = settings
+        .iter()
+        .map(|(key, setting)| {
+            let is_set = content
+                .lines()
+                .any(|line| parse(line).map(|(x, _)| x == key).unwrap_or(false));
+            match (setting, is_set) {
+                (Setting::List(_), false) => (format!("extra-{}", key), key.clone()),
+                _ => (key.clone(), key.clone()),
+            }
+        })
+        .collect();
+
+    let mut lines: Vec<String> = vec![];
+    let mut written: IndexMap<String, usize> = IndexMap::new();
+    for line in content.lines() {
+        let (key, value) = match parse(line) {
+            Some((key, value)) if keys.contains_key(key) => (key.to_string(), value),
+            _ => {
+                lines.push(line.to_string());
+                continue;
+            }
+        };
+        let setting = &settings[&keys[&key]];
+        match (written.get(&key), setting) {
+            (Some(index), Setting::List(_)) => {
+                let values = lines[*index].split_once('=').unwrap().1.trim().to_string();
+                lines[*index] = format!("{} = {}", key, union(&values, value, &[]));
+            }
+            (Some(_), Setting::Value(_)) => {}
+            (None, Setting::List(values)) => {
+                written.insert(key.clone(), lines.len());
+                lines.push(format!("{} = {}", key, union(value, "", values)));
+            }
+            (None, Setting::Value(value)) => {
+                written.insert(key.clone(), lines.len());
+                lines.push(format!("{} = {}", key, value));
+            }
+        }
+    }
+
+    for (key, name) in &keys {
+        if written.contains_key(key) {
+            continue;
+        }
+        match &settings[name] {
+            Setting::List(values) => lines.push(format!("{} = {}", key, values.join(" "))),
+            Setting::Value(value) => lines.push(format!("{} = {}", key, value)),
+        }
+    }
+
+    match lines.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", lines.join("\n")),
+    }
+}
+
+/// Space separated values of `a` and `b`, followed by the missing `values`.
+fn union(a: &str, b: &str, values: &[String]) -> String {
+    let mut output: Vec<&str> = vec![];
+    for value in a
+        .split_whitespace()
+        .chain(b.split_whitespace())
+        .chain(values.iter().map(|x| x.as_str()))
+    {
+        if !output.contains(&value) {
+            output.push(value);
+        }
+    }
+    output.join(" ")
+}
+
+fn restart_daemon(session: Option<Session>) -> Result<(), Error> {
+    let command = "if command -v systemctl > /dev/null && systemctl list-unit-files nix-daemon.service > /dev/null 2>&1; then sudo systemctl restart nix-daemon; elif [ -f /Library/LaunchDaemons/org.nixos.nix-daemon.plist ]; then sudo launchctl kickstart -k system/org.nixos.nix-daemon; else sudo pkill nix-daemon || true; fi";
+    println!("   Restarting {}", "nix-daemon".bright_green());
+    download::exec(command, session)?;
+    Ok(())
+}
+
+/// Merges a `nix_conf` block into `/etc/nix/nix.conf`. The previous file is
+/// backed up and the nix daemon restarted only when something changed.
+pub fn apply(conf: &NixConf, session: Option<Session>) -> Result<bool, Error> {
+    let user = download::capture("id -un", session.clone())?
+        .trim()
+        .to_string();
+    let content = match session.clone() {
+        Some(session) => crosup_ssh::read_file(session, NIX_CONF)?,
+        None => std::fs::read(NIX_CONF).ok(),
+    };
+    let content = String::from_utf8(content.unwrap_or_default())?;
+    let merged = merge(&content, &settings(conf, &user));
+    if merged == content {
+        println!("-> {} is up to date", NIX_CONF.bright_green());
+        return Ok(false);
+    }
+
+    println!("-> Updating {}", NIX_CONF.bright_green());
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let staging = format!("/tmp/crosup-nix-{}.conf", timestamp);
+    match session.clone() {
+        Some(session) => crosup_ssh::write_file(session, &staging, merged.as_bytes(), 0o644)?,
+        None => std::fs::write(&staging, &merged)?,
+    }
+    let backup = format!("{}.crosup-{}.bak", NIX_CONF, timestamp);
+    if !content.is_empty() {
+        println!("   Backing up {} to {}", NIX_CONF, backup.bright_green());
+    }
+    download::exec(
+        &format!(
+            "sudo mkdir -p /etc/nix && (test ! -f {} || sudo cp {} {}) && sudo install -m 644 {} {} && rm -f {}",
+            NIX_CONF, NIX_CONF, backup, staging, NIX_CONF, staging
+        ),
+        session.clone(),
+    )?;
+    restart_daemon(session)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let conf = NixConf {
+            trusted_users: Some(vec!["root".into(), "$USER".into()]),
+            experimental_features: Some(vec!["nix-command".into(), "flakes".into()]),
+            substituters: Some(vec!["https://devenv.cachix.org".into()]),
+            settings: [("max_jobs".into(), "auto".into())].into_iter().collect(),
+            ..Default::default()
+        };
+        let settings = settings(&conf, "tsiry");
+
+        let content = "build-users-group = nixbld\nexperimental-features = nix-command\nmax-jobs = 4\ntrusted-users = root\ntrusted-users = root bob\n";
+        let merged = merge(content, &settings);
+        assert_eq!(
+            merged,
+            "build-users-group = nixbld\n\
+             experimental-features = nix-command flakes\n\
+             max-jobs = auto\n\
+             trusted-users = root tsiry bob\n\
+             extra-substituters = https://devenv.cachix.org\n"
+        );
+        assert_eq!(merge(&merged, &settings), merged);
+    }
+}
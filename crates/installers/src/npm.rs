@@ -0,0 +1,291 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_bash_capture, exec_bash_with_output, npm_install};
+use crosup_types::npm::Package;
+use owo_colors::OwoColorize;
+use semver::{Version, VersionReq};
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct NpmInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub manager: String,
+    pub prefix: Option<String>,
+    pub node: Option<String>,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for NpmInstaller {
+    fn from(pkg: Package) -> Self {
+        let mut dependencies = vec!["node".into()];
+        dependencies.extend(pkg.depends_on.unwrap_or(vec![]));
+        Self {
+            name: pkg.name,
+            version: pkg.version.unwrap_or("latest".into()),
+            dependencies,
+            manager: pkg.manager.unwrap_or("npm".into()),
+            prefix: pkg.prefix,
+            node: pkg.node,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "npm".into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Checks a `node --version` output (e.g. `v20.11.0`) against a requirement
+/// such as `20`, `20.11` or `>=18`.
+pub fn node_satisfies(requirement: &str, version: &str) -> bool {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim()
+            .trim_start_matches('v')
+            .split('.')
+            .filter_map(|x| x.parse::<u64>().ok())
+            .collect()
+    };
+
+    let current = parse(version);
+
+    if let Some(minimum) = requirement.trim().strip_prefix(">=") {
+        let minimum = parse(minimum);
+        for (i, part) in minimum.iter().enumerate() {
+            let actual = current.get(i).copied().unwrap_or(0);
+            if actual != *part {
+                return actual > *part;
+            }
+        }
+        return true;
+    }
+
+    let expected = parse(requirement);
+    !expected.is_empty() && current.starts_with(&expected)
+}
+
+/// Whether an installed package version matches the version of the
+/// configuration: `latest`, an exact version or a range such as `^5`,
+/// `>=18 <20` or `^4 || ^5`.
+pub fn version_matches(spec: &str, version: &str) -> bool {
+    let spec = spec.trim();
+    if spec == "latest" {
+        return true;
+    }
+    let version = match Version::parse(version.trim()) {
+        Ok(version) => version,
+        Err(_) => return spec == version.trim(),
+    };
+    // a plain version is exact for npm, a caret requirement for semver
+    if let Ok(exact) = Version::parse(spec) {
+        return exact == version;
+    }
+    spec.split("||").any(|range| {
+        let range = range.split_whitespace().collect::<Vec<_>>().join(", ");
+        VersionReq::parse(&range)
+            .map(|requirement| requirement.matches(&version))
+            .unwrap_or(false)
+    })
+}
+
+impl NpmInstaller {
+    fn package(&self) -> String {
+        match self.version.as_str() {
+            "latest" => self.name.clone(),
+            version => format!("{}@{}", self.name, version),
+        }
+    }
+
+    fn path(&self) -> String {
+        match self.prefix.clone() {
+            Some(prefix) => format!("PATH={}/bin:$PATH", prefix),
+            None => "PATH=$PATH".into(),
+        }
+    }
+
+    pub fn command(&self) -> String {
+        let package = self.package();
+        match (self.manager.as_str(), self.prefix.clone()) {
+            ("pnpm", Some(prefix)) => format!(
+                "PNPM_HOME={} {} pnpm add -g {}",
+                prefix,
+                self.path(),
+                package
+            ),
+            ("pnpm", None) => format!("pnpm add -g {}", package),
+            ("yarn", Some(prefix)) => format!(
+                "{} yarn global add {} --prefix {}",
+                self.path(),
+                package,
+                prefix
+            ),
+            ("yarn", None) => format!("yarn global add {}", package),
+            (_, Some(prefix)) => format!(
+                "{} npm install -g --prefix {} {}",
+                self.path(),
+                prefix,
+                package
+            ),
+            (_, None) => format!("npm install -g {}", package),
+        }
+    }
+
+    pub fn check_node_version(&self) -> Result<(), Error> {
+        if let Some(requirement) = self.node.clone() {
+            let version = exec_bash_capture!("node --version", self.session.clone());
+            if !node_satisfies(&requirement, &version) {
+                return Err(Error::msg(format!(
+                    "{} requires node {}, found {}",
+                    self.name,
+                    requirement,
+                    version.trim()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn list_global_packages(&self) -> Result<serde_json::Value, Error> {
+        let prefix = match self.prefix.clone() {
+            Some(prefix) => format!("--prefix {}", prefix),
+            None => "".into(),
+        };
+        let command = match self.manager.as_str() {
+            "pnpm" => "pnpm ls -g --json --depth=0 || true".into(),
+            _ => format!("npm ls -g --json --depth=0 {} || true", prefix),
+        };
+        let output = exec_bash_capture!(&command, self.session.clone());
+        let value: serde_json::Value = serde_json::from_str(&output)?;
+        match value {
+            serde_json::Value::Array(mut values) if !values.is_empty() => Ok(values.remove(0)),
+            value => Ok(value),
+        }
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+}
+
+impl Installer for NpmInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        self.check_node_version()?;
+
+        println!("-> 🚚 Installing {}", self.name().bright_green());
+
+        let command = self.command();
+        println!("-> Running {}", command.bright_green());
+        npm_install!(command, self.name, self.session.clone());
+
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+
+        if self.manager == "yarn" {
+            let command = self.version_check.clone().unwrap_or(self.name.clone());
+            check_version!(self, command, self.session.clone());
+            return Ok(false);
+        }
+
+        let packages = self.list_global_packages()?;
+        match packages["dependencies"][&self.name]["version"].as_str() {
+            Some(version) if version_matches(&self.version, version) => {
+                println!("   {}@{}", self.name.cyan(), version.cyan());
+                Ok(true)
+            }
+            _ => Err(Error::msg(format!("{} is not installed", self.name))),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_satisfies() {
+        assert!(node_satisfies("20", "v20.11.0"));
+        assert!(node_satisfies("20.11", "v20.11.1"));
+        assert!(!node_satisfies("20.11", "v20.10.0"));
+        assert!(!node_satisfies("18", "v20.11.0"));
+        assert!(node_satisfies(">=18", "v20.11.0"));
+        assert!(node_satisfies(">=20.11", "v20.11.0"));
+        assert!(!node_satisfies(">=21", "v20.11.0"));
+    }
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("latest", "5.4.5"));
+        assert!(version_matches("5.4.5", "5.4.5"));
+        assert!(!version_matches("5.4.0", "5.4.5"));
+        assert!(version_matches("^5", "5.4.5"));
+        assert!(!version_matches("^5", "4.9.5"));
+        assert!(version_matches(">=18", "20.11.0"));
+        assert!(version_matches("~5.4.0", "5.4.5"));
+        assert!(!version_matches("~5.3.0", "5.4.5"));
+        assert!(version_matches(">=18 <20", "19.0.1"));
+        assert!(!version_matches(">=18 <20", "20.0.0"));
+        assert!(version_matches("^4 || ^5", "5.0.0"));
+        assert!(version_matches("5.x", "5.4.5"));
+        assert!(!version_matches("next", "5.4.5"));
+    }
+}
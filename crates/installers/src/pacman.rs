@@ -5,7 +5,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct PacmanInstaller {
@@ -15,6 +15,7 @@ pub struct PacmanInstaller {
     pub pacman_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub non_interactive: bool,
     pub provider: String,
@@ -28,6 +29,7 @@ impl From<Package> for PacmanInstaller {
             packages: pkg.packages,
             pacman_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "pacman".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             ..Default::default()
         }
@@ -35,6 +37,10 @@ impl From<Package> for PacmanInstaller {
 }
 
 impl PacmanInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.pacman_dependencies.is_empty() {
             return Ok(());
@@ -71,6 +77,7 @@ impl Installer for PacmanInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -85,6 +92,7 @@ impl Installer for PacmanInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
@@ -0,0 +1,170 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_bash_with_output, pipx_install};
+use crosup_types::pipx::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{download, shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct PipxInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub extras: Option<Vec<String>>,
+    pub git: Option<String>,
+    pub python: Option<String>,
+    pub include_deps: Option<bool>,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for PipxInstaller {
+    fn from(pkg: Package) -> Self {
+        let mut dependencies = vec!["python".into()];
+        dependencies.extend(pkg.depends_on.unwrap_or(vec![]));
+        Self {
+            name: pkg.name,
+            version: pkg.version.unwrap_or("latest".into()),
+            dependencies,
+            extras: pkg.extras,
+            git: pkg.git,
+            python: pkg.python,
+            include_deps: pkg.include_deps,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "pipx".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl PipxInstaller {
+    pub fn spec(&self) -> String {
+        let extras = match self.extras.clone() {
+            Some(extras) => format!("[{}]", extras.join(",")),
+            None => "".into(),
+        };
+
+        if let Some(git) = self.git.clone() {
+            return format!("\"{}{} @ git+{}\"", self.name, extras, git);
+        }
+
+        match self.version.as_str() {
+            "latest" => format!("\"{}{}\"", self.name, extras),
+            version => format!("\"{}{}=={}\"", self.name, extras, version),
+        }
+    }
+
+    pub fn options(&self) -> String {
+        let mut options = vec![];
+
+        if let Some(python) = self.python.clone() {
+            options.push(format!("--python {}", python));
+        }
+
+        if self.include_deps.unwrap_or(false) {
+            options.push("--include-deps".into());
+        }
+
+        options.join(" ")
+    }
+
+    pub fn ensure_pipx(&self) -> Result<(), Error> {
+        let command = "type pipx > /dev/null 2>&1 || python3 -m pip install --user pipx";
+        exec_bash_with_output!(command, self.session.clone());
+        Ok(())
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+}
+
+impl Installer for PipxInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        println!("-> 🚚 Installing {}", self.name().bright_green());
+        download::require(
+            &["pipx", "python3"],
+            "$HOME/.local/bin",
+            "python",
+            &self.name,
+            self.session.clone(),
+        )?;
+        self.ensure_pipx()?;
+
+        let spec = self.spec();
+        let options = self.options();
+        let command = format!("pipx install {} {}", options, spec);
+        println!("-> Running {}", command.bright_green());
+        pipx_install!(spec, options, self.session.clone());
+
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        if let Some(command) = self.version_check.clone() {
+            check_version!(self, command, self.session.clone());
+            return Ok(false);
+        }
+        let command = self.name.clone();
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
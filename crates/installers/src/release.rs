@@ -0,0 +1,427 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_bash_with_output};
+use crosup_types::release::Package;
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{download, shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct ReleaseInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub repo: String,
+    pub tag: String,
+    pub asset: String,
+    pub sha256: Option<String>,
+    pub checksums: Option<String>,
+    pub binaries: Vec<String>,
+    pub bin_dir: String,
+    pub base_url: String,
+    pub os_map: IndexMap<String, String>,
+    pub arch_map: IndexMap<String, String>,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for ReleaseInstaller {
+    fn from(pkg: Package) -> Self {
+        Self {
+            name: pkg.name,
+            version: pkg.tag.clone(),
+            dependencies: pkg.depends_on.unwrap_or(vec![]),
+            repo: pkg.repo,
+            tag: pkg.tag,
+            asset: pkg.asset,
+            sha256: pkg.sha256,
+            checksums: pkg.checksums,
+            binaries: pkg.binaries.unwrap_or(vec![]),
+            bin_dir: pkg.bin_dir.unwrap_or("~/.local/bin".into()),
+            base_url: pkg.base_url.unwrap_or("https://github.com".into()),
+            os_map: pkg.os_map.unwrap_or_default(),
+            arch_map: pkg.arch_map.unwrap_or_default(),
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            provider: "release".into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl ReleaseInstaller {
+    /// Replaces the `{os}`, `{arch}`, `{tag}` and `{version}` placeholders
+    /// of an asset name.
+    pub fn render(&self, template: &str, os: &str, arch: &str) -> String {
+        let os = self.os_map.get(os).map(|x| x.as_str()).unwrap_or(os);
+        let arch = self.arch_map.get(arch).map(|x| x.as_str()).unwrap_or(arch);
+        template
+            .replace("{os}", os)
+            .replace("{arch}", arch)
+            .replace("{tag}", &self.tag)
+            .replace("{version}", self.tag.trim_start_matches('v'))
+    }
+
+    pub fn asset_url(&self, asset: &str) -> String {
+        format!(
+            "{}/{}/releases/download/{}/{}",
+            self.base_url.trim_end_matches('/'),
+            self.repo,
+            self.tag,
+            asset
+        )
+    }
+
+    fn work_dir(&self) -> String {
+        format!("/tmp/crosup-release-{}", self.name)
+    }
+
+    fn bin_dir(&self) -> String {
+        self.bin_dir.replacen("~", "$HOME", 1)
+    }
+
+    fn expected_checksum(
+        &self,
+        asset: &str,
+        os: &str,
+        arch: &str,
+    ) -> Result<Option<String>, Error> {
+        if let Some(sha256) = self.sha256.clone() {
+            return Ok(Some(sha256));
+        }
+
+        if let Some(checksums) = self.checksums.clone() {
+            let checksums = self.render(&checksums, os, arch);
+            let path = format!("{}/{}", self.work_dir(), checksums);
+            download::download(&self.asset_url(&checksums), &path, self.session.clone())?;
+            let content = download::exec(&format!("cat \"{}\"", path), self.session.clone())?;
+            return match download::find_checksum(&content, asset) {
+                Some(checksum) => Ok(Some(checksum)),
+                None => Err(Error::msg(format!(
+                    "No checksum found for {} in {}",
+                    asset, checksums
+                ))),
+            };
+        }
+
+        Ok(None)
+    }
+
+    fn install_binaries(&self, archive: &str) -> Result<(), Error> {
+        let bin_dir = self.bin_dir();
+        download::exec(&format!("mkdir -p \"{}\"", bin_dir), self.session.clone())?;
+
+        if !download::is_archive(archive) {
+            let binary = self.binaries.first().unwrap_or(&self.name);
+            let target = format!("{}/{}", bin_dir, binary);
+            println!("   Installing {} to {}", binary.bright_green(), target);
+            download::exec(
+                &format!("install -m 0755 \"{}\" \"{}\"", archive, target),
+                self.session.clone(),
+            )?;
+            return Ok(());
+        }
+
+        let extracted = format!("{}/extracted", self.work_dir());
+        download::extract(archive, &extracted, 0, self.session.clone())?;
+
+        let binaries = match self.binaries.is_empty() {
+            true => vec![self.name.clone()],
+            false => self.binaries.clone(),
+        };
+
+        for binary in binaries {
+            let filename = binary.rsplit('/').next().unwrap_or(&binary);
+            let source = match binary.contains('/') {
+                true => format!("{}/{}", extracted, binary),
+                false => download::exec(
+                    &format!(
+                        "find \"{}\" -type f -name \"{}\" | head -n 1",
+                        extracted, filename
+                    ),
+                    self.session.clone(),
+                )?
+                .trim()
+                .to_string(),
+            };
+
+            if source.is_empty() {
+                return Err(Error::msg(format!(
+                    "{} not found in {}",
+                    binary, self.asset
+                )));
+            }
+
+            let target = format!("{}/{}", bin_dir, filename);
+            println!("   Installing {} to {}", filename.bright_green(), target);
+            download::exec(
+                &format!("install -m 0755 \"{}\" \"{}\"", source, target),
+                self.session.clone(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_bash_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
+    pub fn download_and_install(&self) -> Result<(), Error> {
+        let (os, arch) = download::platform(self.session.clone())?;
+        let asset = self.render(&self.asset, &os, &arch);
+        let work_dir = self.work_dir();
+        let archive = format!("{}/{}", work_dir, asset);
+
+        download::exec(
+            &format!("rm -rf \"{}\" && mkdir -p \"{}\"", work_dir, work_dir),
+            self.session.clone(),
+        )?;
+        download::download(&self.asset_url(&asset), &archive, self.session.clone())?;
+
+        match self.expected_checksum(&asset, &os, &arch)? {
+            Some(checksum) => download::verify_sha256(&archive, &checksum, self.session.clone())?,
+            None => println!(
+                "   {}",
+                format!("No checksum provided for {}, skipping verification", asset).yellow()
+            ),
+        }
+
+        self.install_binaries(&archive)?;
+
+        download::exec(&format!("rm -rf \"{}\"", work_dir), self.session.clone())?;
+        Ok(())
+    }
+}
+
+impl Installer for ReleaseInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+        println!(
+            "-> 🚚 Installing {} {} from {}",
+            self.name().bright_green(),
+            self.tag,
+            self.repo
+        );
+
+        self.download_and_install()?;
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        let command = match self.version_check.clone() {
+            Some(command) => command,
+            None => match self.binaries.first() {
+                Some(binary) => binary.rsplit('/').next().unwrap_or(binary).to_string(),
+                None => self.name.clone(),
+            },
+        };
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        io::{Read, Write},
+        net::TcpListener,
+        os::unix::fs::PermissionsExt,
+        path::Path,
+    };
+
+    /// Serves `files` over HTTP on a random local port, standing in for
+    /// github.com release downloads.
+    fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buffer = [0; 4096];
+                let n = stream.read(&mut buffer).unwrap();
+                let request = String::from_utf8_lossy(&buffer[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                match files.get(path) {
+                    Some(body) => {
+                        let header = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        );
+                        stream.write_all(header.as_bytes()).unwrap();
+                        stream.write_all(body).unwrap();
+                    }
+                    None => {
+                        stream
+                            .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                            .unwrap();
+                    }
+                }
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn build_archive(name: &str) -> (String, Vec<u8>) {
+        let dir = std::env::temp_dir().join(format!("crosup-test-archive-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("hello-1.0.0/bin")).unwrap();
+        std::fs::write(dir.join("hello-1.0.0/bin/hello"), "#!/bin/sh\necho hello\n").unwrap();
+        let archive = dir.join("hello.tar.gz");
+        let status = std::process::Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&dir)
+            .arg("hello-1.0.0")
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let bytes = std::fs::read(&archive).unwrap();
+        (sha256::digest(bytes.as_slice()), bytes)
+    }
+
+    fn installer(name: &str, base_url: &str, bin_dir: &Path) -> ReleaseInstaller {
+        ReleaseInstaller {
+            name: name.into(),
+            ..ReleaseInstaller::from(Package {
+                name: name.into(),
+                repo: "crosup/hello".into(),
+                tag: "v1.0.0".into(),
+                asset: "hello-{version}-{os}-{arch}.tar.gz".into(),
+                binaries: Some(vec!["hello".into()]),
+                bin_dir: Some(bin_dir.to_string_lossy().to_string()),
+                base_url: Some(base_url.into()),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn asset_path() -> String {
+        let (os, arch) = download::platform(None).unwrap();
+        format!(
+            "/crosup/hello/releases/download/v1.0.0/hello-1.0.0-{}-{}.tar.gz",
+            os, arch
+        )
+    }
+
+    #[test]
+    fn test_install_release_with_sha256() {
+        let (checksum, bytes) = build_archive("sha256");
+        let base_url = serve([(asset_path(), bytes)].into_iter().collect());
+        let bin_dir = std::env::temp_dir().join("crosup-test-bin-sha256");
+        let _ = std::fs::remove_dir_all(&bin_dir);
+
+        let installer = ReleaseInstaller {
+            sha256: Some(checksum),
+            ..installer("hello-sha256", &base_url, &bin_dir)
+        };
+        installer.download_and_install().unwrap();
+
+        let metadata = std::fs::metadata(bin_dir.join("hello")).unwrap();
+        assert!(metadata.permissions().mode() & 0o111 != 0);
+    }
+
+    #[test]
+    fn test_install_release_with_checksums_file() {
+        let (checksum, bytes) = build_archive("checksums");
+        let asset = asset_path();
+        let filename = asset.rsplit('/').next().unwrap().to_string();
+        let checksums = format!("{}  {}\n", checksum, filename);
+        let base_url = serve(
+            [
+                (asset, bytes),
+                (
+                    "/crosup/hello/releases/download/v1.0.0/checksums.txt".into(),
+                    checksums.into_bytes(),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let bin_dir = std::env::temp_dir().join("crosup-test-bin-checksums");
+        let _ = std::fs::remove_dir_all(&bin_dir);
+
+        let installer = ReleaseInstaller {
+            checksums: Some("checksums.txt".into()),
+            ..installer("hello-checksums", &base_url, &bin_dir)
+        };
+        installer.download_and_install().unwrap();
+
+        assert!(bin_dir.join("hello").exists());
+    }
+
+    #[test]
+    fn test_install_release_with_wrong_sha256() {
+        let (_, bytes) = build_archive("mismatch");
+        let base_url = serve([(asset_path(), bytes)].into_iter().collect());
+        let bin_dir = std::env::temp_dir().join("crosup-test-bin-mismatch");
+        let _ = std::fs::remove_dir_all(&bin_dir);
+
+        let installer = ReleaseInstaller {
+            sha256: Some("0".repeat(64)),
+            ..installer("hello-mismatch", &base_url, &bin_dir)
+        };
+
+        assert!(installer.download_and_install().is_err());
+        assert!(!bin_dir.join("hello").exists());
+    }
+}
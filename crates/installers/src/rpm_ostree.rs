@@ -0,0 +1,154 @@
+use anyhow::Error;
+use crosup_macros::{check_version, exec_sh_with_output, rpm_ostree_install};
+use crosup_types::rpm_ostree::Package;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::{shell, Installer};
+
+#[derive(Default, Clone)]
+pub struct RpmOstreeInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub rpm_ostree_dependencies: Vec<String>,
+    pub packages: Option<Vec<String>>,
+    pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
+    pub version_check: Option<String>,
+    pub apply_live: bool,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Package> for RpmOstreeInstaller {
+    fn from(pkg: Package) -> Self {
+        Self {
+            name: pkg.name,
+            packages: pkg.packages,
+            rpm_ostree_dependencies: pkg.depends_on.unwrap_or(vec![]),
+            provider: "rpm-ostree".into(),
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init.unwrap_or_default(),
+            version_check: pkg.version_check,
+            apply_live: pkg.apply_live.unwrap_or(false),
+            ..Default::default()
+        }
+    }
+}
+
+impl RpmOstreeInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
+    fn options(&self) -> &str {
+        match self.apply_live {
+            true => "--apply-live",
+            false => "",
+        }
+    }
+
+    pub fn install_dependencies(&self) -> Result<(), Error> {
+        if self.rpm_ostree_dependencies.is_empty() {
+            return Ok(());
+        }
+
+        println!(
+            "-> Installing dependencies for {}",
+            self.name.bright_green()
+        );
+        let deps = self.rpm_ostree_dependencies.join(" ");
+        rpm_ostree_install!(deps, self.options(), self.session.clone());
+        Ok(())
+    }
+
+    fn postinstall(&self) -> Result<(), Error> {
+        if let Some(command) = self.postinstall.clone() {
+            println!(
+                "-> Running postinstall command:\n{}",
+                command.bright_green()
+            );
+            for cmd in command.split("\n") {
+                exec_sh_with_output!(cmd, self.session.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Installer for RpmOstreeInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            self.shell_init()?;
+            return Ok(());
+        }
+
+        self.install_dependencies()?;
+
+        if let Some(packages) = self.packages.clone() {
+            let packages = packages.join(" ");
+            let command = format!(
+                "sudo rpm-ostree install -y --idempotent --allow-inactive {} {}",
+                self.options(),
+                packages
+            );
+            println!("-> Running {}", command.bright_green());
+            rpm_ostree_install!(packages, self.options(), self.session.clone());
+
+            if !self.apply_live {
+                println!(
+                    "-> {} was layered, reboot to apply the new deployment",
+                    self.name().bright_green()
+                );
+            }
+        }
+
+        self.postinstall()?;
+        self.shell_init()?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} is already installed",
+            self.name.bright_green()
+        );
+        if let Some(command) = self.version_check.clone() {
+            check_version!(self, command, self.session.clone());
+            return Ok(false);
+        }
+        let command = self.name.clone();
+        check_version!(self, command, self.session.clone());
+        Ok(false)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
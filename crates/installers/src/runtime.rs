@@ -0,0 +1,184 @@
+use anyhow::Error;
+use crosup_macros::{exec_bash_capture, runtime_install};
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::{any::Any, io::BufRead, process::Stdio};
+
+use super::Installer;
+
+#[derive(Default, Clone)]
+pub struct RuntimeInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub manager: String,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl RuntimeInstaller {
+    pub fn new(name: &str, version: &str, manager: &str) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            dependencies: vec![manager.into()],
+            manager: manager.into(),
+            provider: "runtime".into(),
+            ..Default::default()
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self.manager.as_str() {
+            "asdf" => "PATH=$HOME/.local/bin:$HOME/.asdf/shims:$PATH",
+            _ => "PATH=$HOME/.local/bin:$HOME/.local/share/mise/shims:$PATH",
+        }
+    }
+
+    /// Version filter passed to the version manager, `latest` matches any version.
+    fn filter(&self) -> &str {
+        match self.version.as_str() {
+            "latest" => "",
+            version => version,
+        }
+    }
+
+    pub fn command(&self) -> String {
+        match self.manager.as_str() {
+            "asdf" => format!(
+                "export {}; (asdf plugin list 2>/dev/null | grep -qx {} || asdf plugin add {}) && VERSION=$(asdf latest {} {}) && asdf install {} $VERSION && asdf set -u {} $VERSION",
+                self.path(),
+                self.name,
+                self.name,
+                self.name,
+                self.filter(),
+                self.name,
+                self.name
+            ),
+            _ => format!(
+                "export {}; mise install {}@{} && mise use -g {}@{}",
+                self.path(),
+                self.name,
+                self.version,
+                self.name,
+                self.version
+            ),
+        }
+    }
+
+    fn current(&self) -> Result<String, Error> {
+        let command = format!(
+            "export {}; {} where {} > /dev/null && {} current {}",
+            self.path(),
+            self.manager,
+            self.name,
+            self.manager,
+            self.name
+        );
+        Ok(exec_bash_capture!(&command, self.session.clone()))
+    }
+}
+
+/// Checks whether the output of `mise current`/`asdf current` contains a
+/// version matching `requirement` (`20.11` matches `20.11.1`, not `20.1.0`).
+pub fn version_matches(requirement: &str, output: &str) -> bool {
+    if requirement == "latest" {
+        return output.split_whitespace().next().is_some();
+    }
+    output
+        .split_whitespace()
+        .any(|version| version == requirement || version.starts_with(&format!("{}.", requirement)))
+}
+
+impl Installer for RuntimeInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if !["mise", "asdf"].contains(&self.manager.as_str()) {
+            return Err(Error::msg(format!(
+                "Unsupported runtime manager {}, expected mise or asdf",
+                self.manager
+            )));
+        }
+
+        if self.is_installed().is_ok() {
+            println!(
+                "-> {} is already installed, skipping",
+                self.name().bright_green()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "-> 🚚 Installing {} {} with {}",
+            self.name().bright_green(),
+            self.version,
+            self.manager
+        );
+
+        let command = self.command();
+        println!("   Running {}", command.bright_green());
+        runtime_install!(command, self.name, self.session.clone());
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!(
+            "-> Checking if {} {} is already installed",
+            self.name.bright_green(),
+            self.version
+        );
+        let current = self.current()?;
+        match version_matches(&self.version, &current) {
+            true => {
+                println!("   {}", current.trim().cyan());
+                Ok(true)
+            }
+            false => Err(Error::msg(format!(
+                "{} {} is not installed",
+                self.name, self.version
+            ))),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("20.11", "20.11.1"));
+        assert!(version_matches(
+            "20.11",
+            "node 20.11.0 ~/.tool-versions true"
+        ));
+        assert!(version_matches("3.12.1", "3.12.1"));
+        assert!(!version_matches("20.1", "20.11.1"));
+        assert!(!version_matches("20", "18.19.0"));
+        assert!(version_matches("latest", "22.1.0"));
+        assert!(!version_matches("latest", ""));
+    }
+}
@@ -0,0 +1,268 @@
+use anyhow::Error;
+use crosup_types::service::Service;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+use std::any::Any;
+
+use super::{download, Installer};
+
+#[derive(Default, Clone)]
+pub struct ServiceInstaller {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<String>,
+    pub unit: String,
+    pub enable: bool,
+    pub start: bool,
+    pub user: bool,
+    pub groups: Vec<String>,
+    pub provider: String,
+    pub session: Option<Session>,
+}
+
+impl From<Service> for ServiceInstaller {
+    fn from(service: Service) -> Self {
+        // the service is set up after the package of the same name
+        let mut dependencies = vec![service.name.clone()];
+        dependencies.extend(service.depends_on.unwrap_or(vec![]));
+        Self {
+            name: format!("{}-service", service.name),
+            version: "latest".into(),
+            dependencies,
+            unit: service.unit.unwrap_or(service.name),
+            enable: service.enable.unwrap_or(true),
+            start: service.start.unwrap_or(true),
+            user: service.user.unwrap_or(false),
+            groups: service.groups.unwrap_or(vec![]),
+            provider: "service".into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// What has to be done to bring a service to its desired state.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    pub groups: Vec<String>,
+    pub enable: bool,
+    pub start: bool,
+}
+
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty() && !self.enable && !self.start
+    }
+}
+
+impl ServiceInstaller {
+    fn exec(&self, command: &str) -> Result<String, Error> {
+        download::exec(command, self.session.clone())
+    }
+
+    /// Runs a command checking the target machine, which isn't journaled.
+    fn capture(&self, command: &str) -> Result<String, Error> {
+        download::capture(command, self.session.clone())
+    }
+
+    /// `systemctl` invocation for the unit, user units need the runtime
+    /// directory of the user which isn't set in non-login ssh sessions.
+    fn systemctl(&self) -> &str {
+        match self.user {
+            true => "XDG_RUNTIME_DIR=${XDG_RUNTIME_DIR:-/run/user/$(id -u)} systemctl --user",
+            false => "sudo systemctl",
+        }
+    }
+
+    fn has_systemd(&self) -> bool {
+        self.capture("test -d /run/systemd/system").is_ok()
+    }
+
+    /// Whether the unit is known to systemd, it isn't when the package
+    /// providing it hasn't been installed.
+    fn has_unit(&self) -> bool {
+        let systemctl = match self.user {
+            true => self.systemctl(),
+            false => "systemctl",
+        };
+        self.capture(&format!("{} cat {} > /dev/null 2>&1", systemctl, self.unit))
+            .is_ok()
+    }
+
+    /// Queries `systemctl is-enabled`/`is-active`, which exit with an error
+    /// when the unit isn't enabled/active.
+    fn query(&self, command: &str) -> Result<String, Error> {
+        let systemctl = match self.user {
+            true => self.systemctl(),
+            false => "systemctl",
+        };
+        Ok(self
+            .capture(&format!(
+                "{} {} {} 2>/dev/null || true",
+                systemctl, command, self.unit
+            ))?
+            .trim()
+            .to_string())
+    }
+
+    pub fn changes(&self) -> Result<Changes, Error> {
+        let current_groups = self.capture("id -nG")?;
+        let current_groups: Vec<&str> = current_groups.split_whitespace().collect();
+        Ok(Changes {
+            groups: self
+                .groups
+                .iter()
+                .filter(|x| !current_groups.contains(&x.as_str()))
+                .cloned()
+                .collect(),
+            enable: self.enable && self.query("is-enabled")? != "enabled",
+            start: self.start && self.query("is-active")? != "active",
+        })
+    }
+
+    fn add_groups(&self, groups: &[String]) -> Result<(), Error> {
+        for group in groups {
+            println!("   Adding user to group {}", group.bright_green());
+            self.exec(&format!(
+                "(getent group {} > /dev/null || sudo groupadd {}) && sudo usermod -aG {} $(id -un)",
+                group, group, group
+            ))?;
+        }
+        println!(
+            "   {}",
+            "Group membership takes effect on the next login".yellow()
+        );
+        Ok(())
+    }
+}
+
+impl Installer for ServiceInstaller {
+    fn install(&self) -> Result<(), Error> {
+        if !self.has_systemd() {
+            println!(
+                "-> systemd is not available, skipping {}",
+                self.name().bright_green()
+            );
+            return Ok(());
+        }
+
+        if !self.has_unit() {
+            println!(
+                "-> {} is not installed, skipping {}",
+                self.unit.bright_green(),
+                self.name()
+            );
+            return Ok(());
+        }
+
+        let changes = self.changes()?;
+        if changes.is_empty() {
+            println!("-> {} is up to date, skipping", self.name().bright_green());
+            return Ok(());
+        }
+
+        println!("-> ⚙️  Setting up {}", self.unit.bright_green());
+
+        if !changes.groups.is_empty() {
+            self.add_groups(&changes.groups)?;
+        }
+
+        let command = match (changes.enable, changes.start) {
+            (true, true) => Some(format!("{} enable --now {}", self.systemctl(), self.unit)),
+            (true, false) => Some(format!("{} enable {}", self.systemctl(), self.unit)),
+            (false, true) => Some(format!("{} start {}", self.systemctl(), self.unit)),
+            (false, false) => None,
+        };
+        if let Some(command) = command {
+            println!("   Running {}", command.bright_green());
+            self.exec(&command)
+                .map_err(|_| Error::msg(format!("Failed to set up {}", self.unit)))?;
+        }
+        Ok(())
+    }
+
+    fn uninstall(&self) -> Result<(), Error> {
+        println!("-> 🗑️  Disabling {}", self.unit.bright_green());
+        self.exec(&format!("{} disable --now {}", self.systemctl(), self.unit))
+            .map_err(|_| Error::msg(format!("Failed to disable {}", self.unit)))?;
+        Ok(())
+    }
+
+    fn is_installed(&self) -> Result<bool, Error> {
+        println!("-> Checking if {} is up to date", self.name.bright_green());
+        let changes = self.changes()?;
+        match changes.is_empty() {
+            true => Ok(true),
+            false => Err(Error::msg(format!("{} is not set up", self.unit))),
+        }
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn is_default(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_service() {
+        let installer = ServiceInstaller::from(Service {
+            name: "minikube".into(),
+            unit: Some("libvirtd".into()),
+            groups: Some(vec!["libvirt".into()]),
+            depends_on: Some(vec!["qemu".into()]),
+            ..Default::default()
+        });
+        assert_eq!(installer.name, "minikube-service");
+        assert_eq!(installer.unit, "libvirtd");
+        assert_eq!(installer.dependencies, vec!["minikube", "qemu"]);
+        assert_eq!(installer.groups, vec!["libvirt"]);
+        assert!(installer.enable && installer.start && !installer.user);
+
+        let installer = ServiceInstaller::from(Service {
+            name: "syncthing".into(),
+            user: Some(true),
+            start: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(installer.unit, "syncthing");
+        assert!(installer.user && !installer.start);
+        assert!(installer.systemctl().contains("systemctl --user"));
+    }
+
+    #[test]
+    fn test_changes() {
+        assert!(Changes::default().is_empty());
+        assert!(!Changes {
+            groups: vec!["docker".into()],
+            ..Default::default()
+        }
+        .is_empty());
+        assert!(!Changes {
+            start: true,
+            ..Default::default()
+        }
+        .is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use anyhow::Error;
+use indexmap::IndexMap;
+use owo_colors::OwoColorize;
+use ssh2::Session;
+
+use super::download;
+
+const BEGIN: &str = "# >>> crosup >>>";
+const END: &str = "# <<< crosup <<<";
+const HEADER: &str = "# Managed by crosup, changes inside this block will be overwritten.";
+const SECTION: &str = "# crosup: ";
+
+/// Rc file of the user's login shell, in which crosup owns a block holding
+/// the `shell_init` lines of each tool.
+#[derive(Default, Clone)]
+pub struct ShellProfile {
+    pub shell: String,
+    pub path: String,
+    pub session: Option<Session>,
+}
+
+/// Rc file sourced by interactive sessions of `shell`, relative to `$HOME`.
+pub fn rc_file(shell: &str) -> &'static str {
+    match shell {
+        "bash" => ".bashrc",
+        "zsh" => ".zshrc",
+        "fish" => ".config/fish/config.fish",
+        _ => ".profile",
+    }
+}
+
+/// Splits the crosup block of an rc file into the lines owned by each tool.
+fn sections(content: &str) -> IndexMap<String, Vec<String>> {
+    let mut sections: IndexMap<String, Vec<String>> = IndexMap::new();
+    let mut current: Option<String> = None;
+    let mut inside = false;
+    for line in content.lines() {
+        match line {
+            BEGIN => inside = true,
+            END => inside = false,
+            _ if !inside || line == HEADER => {}
+            _ => match line.strip_prefix(SECTION) {
+                Some(tool) => {
+                    current = Some(tool.to_string());
+                    sections.entry(tool.to_string()).or_default();
+                }
+                None => {
+                    if let Some(tool) = current.clone() {
+                        sections.entry(tool).or_default().push(line.to_string());
+                    }
+                }
+            },
+        }
+    }
+    sections
+}
+
+/// Returns `content` with the lines of `tool` in the crosup block replaced by
+/// `lines`. Empty `lines` remove the tool, and the block once it is empty.
+pub fn update_block(content: &str, tool: &str, lines: &[String]) -> String {
+    let mut sections = sections(content);
+    match lines.is_empty() {
+        true => {
+            sections.shift_remove(tool);
+        }
+        false => {
+            sections.insert(tool.to_string(), lines.to_vec());
+        }
+    }
+
+    if sections.is_empty() && !content.lines().any(|x| x == BEGIN) {
+        return content.to_string();
+    }
+
+    let mut before = vec![];
+    let mut after = vec![];
+    let mut found = false;
+    let mut inside = false;
+    for line in content.lines() {
+        match line {
+            BEGIN => {
+                found = true;
+                inside = true;
+            }
+            END => inside = false,
+            _ if inside => {}
+            _ if found => after.push(line),
+            _ => before.push(line),
+        }
+    }
+
+    let mut output: Vec<String> = before.iter().map(|x| x.to_string()).collect();
+    if sections.is_empty() && output.last().map(|x| x.is_empty()).unwrap_or(false) {
+        // blank line separating the block from the rest of the file
+        output.pop();
+    }
+    if !sections.is_empty() {
+        if !found && output.last().map(|x| !x.is_empty()).unwrap_or(false) {
+            output.push("".into());
+        }
+        output.push(BEGIN.into());
+        output.push(HEADER.into());
+        for (tool, lines) in sections {
+            output.push(format!("{}{}", SECTION, tool));
+            output.extend(lines);
+        }
+        output.push(END.into());
+    }
+    output.extend(after.iter().map(|x| x.to_string()));
+
+    match output.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", output.join("\n")),
+    }
+}
+
+impl ShellProfile {
+    /// Finds the login shell of the user from the passwd database (or
+    /// Directory Services on macOS), falling back to `$SHELL`.
+    pub fn detect(session: Option<Session>) -> Result<Self, Error> {
+        let command = "(getent passwd \"$(id -un)\" | cut -d: -f7; dscl . -read \"/Users/$(id -un)\" UserShell | cut -d\" \" -f2) 2>/dev/null | head -n 1";
+        let mut shell = download::capture(command, session.clone())?
+            .trim()
+            .to_string();
+        if shell.is_empty() {
+            shell = download::capture("echo $SHELL", session.clone())?
+                .trim()
+                .to_string();
+        }
+        let shell = shell.rsplit('/').next().unwrap_or_default().to_string();
+        let home = match session {
+            Some(_) => download::capture("echo $HOME", session.clone())?
+                .trim()
+                .to_string(),
+            None => std::env::var("HOME")?,
+        };
+        Ok(Self {
+            path: format!("{}/{}", home, rc_file(&shell)),
+            shell,
+            session,
+        })
+    }
+
+    fn read(&self) -> Result<String, Error> {
+        let content = match self.session.clone() {
+            Some(session) => crosup_ssh::read_file(session, &self.path)?,
+            None => std::fs::read(&self.path).ok(),
+        };
+        Ok(String::from_utf8(content.unwrap_or_default())?)
+    }
+
+    fn write(&self, content: &str) -> Result<(), Error> {
+        download::exec(
+            &format!("mkdir -p \"$(dirname \"{}\")\"", self.path),
+            self.session.clone(),
+        )?;
+        match self.session.clone() {
+            Some(session) => crosup_ssh::write_file(session, &self.path, content.as_bytes(), 0o644),
+            None => Ok(std::fs::write(&self.path, content)?),
+        }
+    }
+
+    /// Replaces the lines of `tool` in the crosup block, returns whether the
+    /// rc file changed.
+    pub fn update(&self, tool: &str, lines: &[String]) -> Result<bool, Error> {
+        let content = self.read()?;
+        let updated = update_block(&content, tool, lines);
+        if updated == content {
+            return Ok(false);
+        }
+        match lines.is_empty() {
+            true => println!(
+                "   Removing {} shell init from {}",
+                tool.bright_green(),
+                self.path
+            ),
+            false => println!(
+                "   Updating {} shell init in {}",
+                tool.bright_green(),
+                self.path
+            ),
+        }
+        self.write(&updated)?;
+        Ok(true)
+    }
+
+    pub fn remove(&self, tool: &str) -> Result<bool, Error> {
+        self.update(tool, &[])
+    }
+}
+
+/// Writes the `shell_init` lines of `tool` to the rc file of the login shell,
+/// does nothing when the tool has none.
+pub fn init(tool: &str, lines: &[String], session: Option<Session>) -> Result<(), Error> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    ShellProfile::detect(session)?.update(tool, lines)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_block() {
+        let brew = vec!["eval \"$(brew shellenv)\"".to_string()];
+        let atuin = vec!["eval \"$(atuin init bash)\"".to_string()];
+
+        let content = update_block("export EDITOR=vim\n", "homebrew", &brew);
+        assert_eq!(
+            content,
+            format!(
+                "export EDITOR=vim\n\n{}\n{}\n# crosup: homebrew\neval \"$(brew shellenv)\"\n{}\n",
+                BEGIN, HEADER, END
+            )
+        );
+        assert_eq!(update_block(&content, "homebrew", &brew), content);
+
+        let content = update_block(&content, "atuin", &atuin);
+        assert_eq!(
+            sections(&content).keys().collect::<Vec<_>>(),
+            vec!["homebrew", "atuin"]
+        );
+
+        let content = update_block(&format!("{}alias ll='ls -l'\n", content), "homebrew", &[]);
+        assert_eq!(sections(&content).keys().collect::<Vec<_>>(), vec!["atuin"]);
+        assert!(content.ends_with(&format!("{}\nalias ll='ls -l'\n", END)));
+
+        let content = update_block(&content, "atuin", &[]);
+        assert_eq!(content, "export EDITOR=vim\nalias ll='ls -l'\n");
+        assert_eq!(update_block(&content, "atuin", &[]), content);
+    }
+}
@@ -5,7 +5,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct YumInstaller {
@@ -15,6 +15,7 @@ pub struct YumInstaller {
     pub yum_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub provider: String,
     pub session: Option<Session>,
@@ -27,6 +28,7 @@ impl From<Package> for YumInstaller {
             packages: pkg.packages,
             yum_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "yum".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             ..Default::default()
         }
@@ -34,6 +36,10 @@ impl From<Package> for YumInstaller {
 }
 
 impl YumInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.yum_dependencies.is_empty() {
             return Ok(());
@@ -70,6 +76,7 @@ impl Installer for YumInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -84,6 +91,7 @@ impl Installer for YumInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
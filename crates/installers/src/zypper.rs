@@ -5,7 +5,7 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{any::Any, io::BufRead, process::Stdio};
 
-use super::Installer;
+use super::{shell, Installer};
 
 #[derive(Default, Clone)]
 pub struct ZypperInstaller {
@@ -15,6 +15,7 @@ pub struct ZypperInstaller {
     pub zypper_dependencies: Vec<String>,
     pub packages: Option<Vec<String>>,
     pub postinstall: Option<String>,
+    pub shell_init: Vec<String>,
     pub version_check: Option<String>,
     pub non_interactive: bool,
     pub provider: String,
@@ -28,6 +29,7 @@ impl From<Package> for ZypperInstaller {
             packages: pkg.packages,
             zypper_dependencies: pkg.depends_on.unwrap_or(vec![]),
             provider: "zypper".into(),
+            shell_init: pkg.shell_init.unwrap_or_default(),
             version_check: pkg.version_check,
             non_interactive: pkg.non_interactive.unwrap_or(true),
             ..Default::default()
@@ -36,6 +38,10 @@ impl From<Package> for ZypperInstaller {
 }
 
 impl ZypperInstaller {
+    fn shell_init(&self) -> Result<(), Error> {
+        shell::init(&self.name, &self.shell_init, self.session.clone())
+    }
+
     pub fn install_dependencies(&self) -> Result<(), Error> {
         if self.zypper_dependencies.is_empty() {
             return Ok(());
@@ -72,6 +78,7 @@ impl Installer for ZypperInstaller {
                     "-> {} is already installed, skipping",
                     self.name().bright_green()
                 );
+                self.shell_init()?;
                 return Ok(());
             }
         }
@@ -90,6 +97,7 @@ impl Installer for ZypperInstaller {
         }
 
         self.postinstall()?;
+        self.shell_init()?;
         Ok(())
     }
 
@@ -1,3 +1,93 @@
+use std::cell::RefCell;
+
+/// Commands run on the current thread since the journal was last taken, one
+/// per line, with their output and the exit status of the last one, so that
+/// install runs can record what a step did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    pub command: String,
+    pub output: Option<String>,
+    pub exit_status: Option<i32>,
+}
+
+impl Journal {
+    fn push(&mut self, command: &str, output: &str, exit_status: Option<i32>) {
+        if !self.command.is_empty() {
+            self.command.push('\n');
+        }
+        self.command.push_str(command);
+        self.exit_status = exit_status;
+
+        if output.trim().is_empty() {
+            return;
+        }
+        let previous = self.output.get_or_insert_with(String::new);
+        if !previous.is_empty() && !previous.ends_with('\n') {
+            previous.push('\n');
+        }
+        previous.push_str(output);
+    }
+}
+
+thread_local! {
+    static JOURNAL: RefCell<Option<Journal>> = const { RefCell::new(None) };
+}
+
+/// Appends a command to the journal of the current thread, `exit_status` is
+/// `None` when the process was killed by a signal.
+pub fn journal(command: &str, output: &str, exit_status: Option<i32>) {
+    JOURNAL.with(|x| {
+        x.borrow_mut()
+            .get_or_insert_with(Journal::default)
+            .push(command, output, exit_status)
+    });
+}
+
+/// Returns the commands run on the current thread and clears them.
+pub fn take_journal() -> Option<Journal> {
+    JOURNAL.with(|journal| journal.borrow_mut().take())
+}
+
+/// Runs a command with `$shell -c`, on the remote machine when there is a
+/// session, streaming its output and recording it in the journal. Evaluates
+/// to the exit status, a non-zero status over ssh fails right away.
+#[macro_export]
+macro_rules! run_command {
+    ($shell:expr, $command:expr, $session:expr) => {{
+        let command = format!("{}", $command);
+        match $session {
+            Some(session) => {
+                let remote = format!("{} -c '{}'", $shell, command);
+                let (status, output) = crosup_ssh::run(session.clone(), &remote)?;
+                $crate::journal(&command, &output, Some(status));
+                crosup_ssh::check_status(&remote, status)?;
+                Some(status)
+            }
+            None => {
+                let mut child = std::process::Command::new($shell)
+                    .arg("-c")
+                    .arg(&command)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn()?;
+                let stdout = child.stdout.take().unwrap();
+                let stdout = std::io::BufReader::new(stdout);
+
+                let mut output = String::new();
+                for line in stdout.lines() {
+                    let line = line?;
+                    println!("{}", line);
+                    output.push_str(&line);
+                    output.push('\n');
+                }
+                let status = child.wait()?.code();
+                $crate::journal(&command, &output, status);
+                status
+            }
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! pipe_curl {
     ($curl:ident) => {
@@ -35,55 +125,19 @@ macro_rules! pipe_brew_curl {
     };
 }
 
-#[macro_export]
-macro_rules! append_to_nix_conf {
-    ($echo:ident) => {
-        let mut tee = std::process::Command::new("bash")
-            .arg("-c")
-            .arg("sudo tee -a /etc/nix/nix.conf")
-            .stdin(Stdio::from($echo.stdout.unwrap()))
-            .stdout(Stdio::piped())
-            .spawn()?;
-        tee.wait()?;
-    };
-}
-
 #[macro_export]
 macro_rules! brew_install {
-    ($self:ident, $package:expr, $cask:expr, $session:expr) => {
-        let mut child = match $cask {
-            true => std::process::Command::new("brew")
-                .arg("install")
-                .arg("--cask")
-                .arg($package)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()?,
-            false => std::process::Command::new("brew")
-                .arg("install")
-                .arg($package)
-                .env(
-                    "PATH",
-                    "/home/linuxbrew/.linuxbrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin",
-                )
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()?,
+    ($self:ident, $package:expr, $cask:expr, $args:expr, $session:expr) => {
+        let command = match $cask {
+            true => format!("brew install --cask {} {}", $args.join(" "), $package),
+            false => format!(
+                "PATH=/home/linuxbrew/.linuxbrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin brew install {} {}",
+                $args.join(" "),
+                $package
+            ),
         };
-
-        let stdout = child.stdout.take().unwrap();
-        let stdout = std::io::BufReader::new(stdout);
-        for line in stdout.lines() {
-            println!("   {}", line.unwrap());
-        }
-
-        let output = child.wait_with_output()?;
-
-        if !output.status.success() {
+        if $crate::run_command!("bash", command, $session) != Some(0) {
             println!("-> Failed to install {}", $self.name().bright_green());
-            if !output.stderr.is_empty() {
-                println!("{}", String::from_utf8_lossy(&output.stderr));
-            }
             return Err(Error::msg(format!("Failed to install {}", $self.name())));
         }
     };
@@ -94,14 +148,17 @@ macro_rules! check_version {
     ($self:ident, $command:expr, $session:expr) => {
         match $session {
             Some(session) => {
-                let command = format!("sh -c 'PATH=$HOME/.nix-profile/bin:/nix/var/nix/profiles/default/bin:/home/linuxbrew/.linuxbrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin type {}'", $command);
+                let command = format!("sh -c 'PATH=$HOME/.nix-profile/bin:/nix/var/nix/profiles/default/bin:/home/linuxbrew/.linuxbrew/bin:$HOME/.cargo/bin:$HOME/go/bin:$HOME/.local/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin type {}'", $command);
                 crosup_ssh::exec(session.clone(), &command)?;
             }
             None => {
                 let home = std::env::var("HOME").unwrap();
                 let mut path = std::env::var("PATH").unwrap();
                 path = format!(
-                    "{}/.nix-profile/bin:/nix/var/nix/profiles/default/bin:/home/linuxbrew/.linuxbrew/bin:{}",
+                    "{}/.nix-profile/bin:/nix/var/nix/profiles/default/bin:/home/linuxbrew/.linuxbrew/bin:{}/.cargo/bin:{}/go/bin:{}/.local/bin:{}",
+                    home,
+                    home,
+                    home,
                     home,
                     path
                 );
@@ -138,453 +195,288 @@ macro_rules! check_version {
 
 #[macro_export]
 macro_rules! exec_bash {
-    ($command:expr,$session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("bash -c '{}'", $command);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("bash")
-                    .arg("-c")
-                    .arg($command)
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                child.wait()?;
-            }
-        };
+    ($command:expr, $session:expr) => {
+        $crate::run_command!("bash", $command, $session);
     };
 }
 
 #[macro_export]
 macro_rules! exec_sh {
     ($command:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sh -c '{}'", $command);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg($command)
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                child.wait()?;
-            }
-        };
+        $crate::run_command!("sh", $command, $session);
     };
 }
 
 #[macro_export]
 macro_rules! exec_bash_with_output {
+    ($command:expr, $session:expr) => {
+        $crate::run_command!("bash", $command, $session);
+    };
+}
+
+#[macro_export]
+macro_rules! exec_bash_capture {
     ($command:expr, $session:expr) => {
         match $session {
             Some(session) => {
                 let command = format!("bash -c '{}'", $command);
-                crosup_ssh::exec(session.clone(), &command)?;
+                crosup_ssh::exec_with_output(session.clone(), &command)?
             }
             None => {
-                let mut child = std::process::Command::new("bash")
+                let output = std::process::Command::new("bash")
                     .arg("-c")
                     .arg($command)
                     .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
+                    .output()?;
+                if !output.status.success() {
+                    return Err(Error::msg(format!("Failed to execute {}", $command)));
                 }
-                child.wait()?;
+                String::from_utf8_lossy(&output.stdout).to_string()
             }
-        };
+        }
     };
 }
 
 #[macro_export]
 macro_rules! exec_sh_with_output {
     ($command:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sh -c '{}'", $command);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg($command)
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                child.wait()?;
-            }
-        };
+        $crate::run_command!("sh", $command, $session);
     };
 }
 
 #[macro_export]
 macro_rules! apt_install {
     ($package:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo apt-get install -y {}", $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo apt-get install -y {}", $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!(
+            "sh",
+            format!("sudo apt-get install -y {}", $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! yum_install {
     ($package:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo yum install -y {}", $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo yum install -y {}", $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!("sh", format!("sudo yum install -y {}", $package), $session)
+            != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! dnf_install {
     ($package:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo dnf install -y {}", $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo dnf install -y {}", $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!("sh", format!("sudo dnf install -y {}", $package), $session)
+            != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! zypper_install {
     ($package:expr, $options:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo zypper install {} {}", $options, $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo zypper install {} {}", $options, $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!(
+            "sh",
+            format!("sudo zypper install {} {}", $options, $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! apk_add {
     ($package:expr, $options:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo apk add {} {}", $options, $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo apk add {} {}", $options, $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!(
+            "sh",
+            format!("sudo apk add {} {}", $options, $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! pacman_install {
     ($package:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo pacman -S {}", $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo pacman -S {}", $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
+        if $crate::run_command!("sh", format!("sudo pacman -S {}", $package), $session) != Some(0) {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
 
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
+#[macro_export]
+macro_rules! xbps_install {
+    ($package:expr, $session:expr) => {
+        if $crate::run_command!(
+            "sh",
+            format!("sudo xbps-install -Sy {}", $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
         }
     };
 }
 
 #[macro_export]
-macro_rules! emerge_install {
+macro_rules! rpm_ostree_install {
     ($package:expr, $options:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo emerge {} {}", $options, $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo emerge {} {}", $options, $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
+        if $crate::run_command!(
+            "sh",
+            format!(
+                "sudo rpm-ostree install -y --idempotent --allow-inactive {} {}",
+                $options, $package
+            ),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
 
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+#[macro_export]
+macro_rules! emerge_install {
+    ($package:expr, $options:expr, $session:expr) => {
+        if $crate::run_command!(
+            "sh",
+            format!("sudo emerge {} {}", $options, $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! slackpkg_install {
     ($package:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo slackpkg install {}", $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo slackpkg {}", $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+        if $crate::run_command!(
+            "sh",
+            format!("sudo slackpkg install {}", $package),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! fleek_install {
     ($package:expr, $options:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("bash -c '. /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run github:ublue-os/fleek -- add {} {}'", $options, $package);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("bash")
-                    .arg("-c")
-                    .arg(format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run github:ublue-os/fleek -- add {} {}", $options, $package))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
+        if $crate::run_command!("bash", format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run github:ublue-os/fleek -- add {} {}", $options, $package), $session) != Some(0) {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
 
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to install {}", $package)));
-                }
-            }
-        };
+#[macro_export]
+macro_rules! cargo_install {
+    ($package:expr, $options:expr, $session:expr) => {
+        if $crate::run_command!(
+            "bash",
+            format!(
+                "PATH=$HOME/.cargo/bin:$PATH cargo install {} {}",
+                $options, $package
+            ),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! go_install {
+    ($package:expr, $options:expr, $session:expr) => {
+        if $crate::run_command!(
+            "bash",
+            format!(
+                "PATH=/usr/local/go/bin:$HOME/go/bin:$PATH go install {} {}",
+                $options, $package
+            ),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! pipx_install {
+    ($package:expr, $options:expr, $session:expr) => {
+        if $crate::run_command!(
+            "bash",
+            format!(
+                "PATH=$HOME/.local/bin:$PATH pipx install {} {}",
+                $options, $package
+            ),
+            $session
+        ) != Some(0)
+        {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! npm_install {
+    ($command:expr, $package:expr, $session:expr) => {
+        if $crate::run_command!("bash", format!("{}", $command), $session) != Some(0) {
+            return Err(Error::msg(format!("Failed to install {}", $package)));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! runtime_install {
+    ($command:expr, $runtime:expr, $session:expr) => {
+        if $crate::run_command!("bash", format!("{}", $command), $session) != Some(0) {
+            return Err(Error::msg(format!("Failed to install {}", $runtime)));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! home_manager_init {
     ($session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("bash -c '. /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- init'");
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("bash")
-                    .arg("-c")
-                    .arg(format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- init"))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to initialize home-manager")));
-                }
-            }
-        };
+        if $crate::run_command!("bash", format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- init"), $session) != Some(0) {
+            return Err(Error::msg("Failed to initialize home-manager"));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! home_manager_switch {
     ($session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("bash -c '. /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- switch'");
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("bash")
-                    .arg("-c")
-                    .arg(format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- switch"))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to run home-manager switch")));
-                }
-            }
-        };
+        if $crate::run_command!("bash", format!(". /nix/var/nix/profiles/default/etc/profile.d/nix-daemon.sh && nix run home-manager/master -- switch"), $session) != Some(0) {
+            return Err(Error::msg("Failed to run home-manager switch"));
+        }
     };
 }
 
 #[macro_export]
 macro_rules! exec_sudo {
     ($command:expr, $session:expr) => {
-        match $session {
-            Some(session) => {
-                let command = format!("sudo {}", $command);
-                crosup_ssh::exec(session.clone(), &command)?;
-            }
-            None => {
-                let mut child = std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(format!("sudo {}", $command))
-                    .stdin(Stdio::piped())
-                    .stdout(Stdio::piped())
-                    .spawn()?;
-                let output = child.stdout.take().unwrap();
-                let output = std::io::BufReader::new(output);
-
-                for line in output.lines() {
-                    println!("{}", line?);
-                }
-                let status = child.wait()?;
-                if !status.success() {
-                    return Err(Error::msg(format!("Failed to execute {}", $command)));
-                }
-            }
+        if $crate::run_command!("sh", format!("sudo {}", $command), $session) != Some(0) {
+            return Err(Error::msg(format!("Failed to execute {}", $command)));
         }
     };
 }
@@ -619,29 +511,6 @@ macro_rules! add_vertex {
     };
 }
 
-#[macro_export]
-macro_rules! add_vertex_with_condition {
-    ($graph:ident, $installer:ident, $config:ident, $pkg_manager:ident, $pkg:ident, $session:ident) => {
-        if let Some(pkg_manager) = &$config.$pkg_manager {
-            if let Some(installer) = pkg_manager.get("install") {
-                match installer.$pkg.clone() {
-                    Some(pkg) => {
-                        pkg.iter().for_each(|(name, x)| {
-                            $graph.add_vertex(Vertex::from(Box::new($installer {
-                                name: name.clone(),
-                                session: $session.clone(),
-                                ..$installer::from(x.clone())
-                            })
-                                as Box<dyn Installer>));
-                        });
-                    }
-                    None => {}
-                }
-            }
-        }
-    };
-}
-
 #[macro_export]
 macro_rules! downcast_installer {
     ($label: expr,$installer: ident, $installer_type: ident) => {
@@ -1,23 +1,34 @@
-use std::fs;
-
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261019_000002_create_run_table;
+mod m20261019_000003_repair_modification_table;
+mod m20261019_000004_create_project_table;
+mod m20261019_000005_add_message_to_modification;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261019_000002_create_run_table::Migration),
+            Box::new(m20261019_000003_repair_modification_table::Migration),
+            Box::new(m20261019_000004_create_project_table::Migration),
+            Box::new(m20261019_000005_add_message_to_modification::Migration),
+        ]
     }
 }
 
 pub async fn run() {
-    let home = std::env::var("HOME").unwrap();
-    let crosup_dir = format!("{}/.config/crosup", home);
-    fs::create_dir_all(&crosup_dir).unwrap();
-    let database_url = format!("sqlite:{}/modifications.sqlite3?mode=rwc", crosup_dir);
+    let database_url = match crosup_repo::database::database_url() {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     std::env::set_var("DATABASE_URL", database_url);
     cli::run_cli(Migrator).await;
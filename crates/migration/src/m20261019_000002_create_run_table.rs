@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Run::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Run::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Run::FileId).integer().not_null())
+                    .col(ColumnDef::new(Run::ModificationId).integer().null())
+                    .col(ColumnDef::new(Run::Host).string().not_null())
+                    .col(ColumnDef::new(Run::Status).string().not_null())
+                    .col(
+                        ColumnDef::new(Run::StartedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".into()),
+                    )
+                    .col(ColumnDef::new(Run::DurationMs).big_integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("run_file_id")
+                            .from(Run::Table, Run::FileId)
+                            .to(File::Table, File::Id),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("run_modification_id")
+                            .from(Run::Table, Run::ModificationId)
+                            .to(Modification::Table, Modification::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(RunStep::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RunStep::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RunStep::RunId).integer().not_null())
+                    .col(ColumnDef::new(RunStep::Tool).string().not_null())
+                    .col(ColumnDef::new(RunStep::Provider).string().not_null())
+                    .col(ColumnDef::new(RunStep::Command).text().null())
+                    .col(ColumnDef::new(RunStep::ExitStatus).integer().not_null())
+                    .col(ColumnDef::new(RunStep::DurationMs).big_integer().not_null())
+                    .col(ColumnDef::new(RunStep::Stdout).text().null())
+                    .col(ColumnDef::new(RunStep::Stderr).text().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("run_step_run_id")
+                            .from(RunStep::Table, RunStep::RunId)
+                            .to(Run::Table, Run::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RunStep::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Run::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum File {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Modification {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum Run {
+    Table,
+    Id,
+    FileId,
+    ModificationId,
+    Host,
+    Status,
+    StartedAt,
+    DurationMs,
+}
+
+#[derive(Iden)]
+enum RunStep {
+    Table,
+    Id,
+    RunId,
+    Tool,
+    Provider,
+    Command,
+    ExitStatus,
+    DurationMs,
+    Stdout,
+    Stderr,
+}
@@ -0,0 +1,386 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, DbBackend, QueryResult},
+};
+
+/// Revisions used to be deduplicated by hash across all files, this drops
+/// the unique constraint on `modification.hash`, gives back their own
+/// revisions to the files which shared one with another file, and rebuilds
+/// the `previous_id` chain of each file.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// SQLite can't drop the constraint of a column, the table is rebuilt
+/// instead, with the foreign keys disabled while the old one is dropped.
+const REBUILD_SQLITE: [&str; 6] = [
+    "PRAGMA foreign_keys = OFF",
+    r#"CREATE TABLE "modification_new" (
+    "id" integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+    "timestamp" text DEFAULT CURRENT_TIMESTAMP,
+    "hash" text NOT NULL,
+    "file_id" integer NOT NULL,
+    "previous_id" integer NULL,
+    "content" text NOT NULL,
+    FOREIGN KEY ("file_id") REFERENCES "file" ("id"),
+    FOREIGN KEY ("previous_id") REFERENCES "modification" ("id")
+)"#,
+    r#"INSERT INTO "modification_new" ("id", "timestamp", "hash", "file_id", "previous_id", "content")
+    SELECT "id", "timestamp", "hash", "file_id", "previous_id", "content" FROM "modification""#,
+    r#"DROP TABLE "modification""#,
+    r#"ALTER TABLE "modification_new" RENAME TO "modification""#,
+    "PRAGMA foreign_keys = ON",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        match manager.get_database_backend() {
+            DbBackend::Sqlite => {
+                for statement in REBUILD_SQLITE {
+                    db.execute_unprepared(statement).await?;
+                }
+            }
+            DbBackend::Postgres => {
+                db.execute_unprepared(
+                    "ALTER TABLE modification DROP CONSTRAINT IF EXISTS modification_hash_key",
+                )
+                .await?;
+            }
+            DbBackend::MySql => {
+                db.execute_unprepared("ALTER TABLE modification DROP INDEX hash")
+                    .await?;
+            }
+        };
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_modification_file_id_hash")
+                    .table(Modification::Table)
+                    .col(Modification::FileId)
+                    .col(Modification::Hash)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        repair_runs(manager).await?;
+        repair_files(manager).await?;
+        rebuild_chains(manager).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_modification_file_id_hash")
+                    .table(Modification::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+async fn query_all(
+    manager: &SchemaManager<'_>,
+    query: &SelectStatement,
+) -> Result<Vec<QueryResult>, DbErr> {
+    let db = manager.get_connection();
+    db.query_all(db.get_database_backend().build(query)).await
+}
+
+/// Inserts a copy of a revision for another file and returns its id.
+async fn copy_modification(
+    manager: &SchemaManager<'_>,
+    id: i32,
+    file_id: i32,
+    timestamp: SimpleExpr,
+) -> Result<i32, DbErr> {
+    let db = manager.get_connection();
+    let insert = Query::insert()
+        .into_table(Modification::Table)
+        .columns([
+            Modification::Timestamp,
+            Modification::Hash,
+            Modification::FileId,
+            Modification::Content,
+        ])
+        .select_from(
+            Query::select()
+                .expr(timestamp)
+                .column(Modification::Hash)
+                .expr(Expr::val(file_id))
+                .column(Modification::Content)
+                .from(Modification::Table)
+                .and_where(Expr::col(Modification::Id).eq(id))
+                .to_owned(),
+        )
+        .map_err(|e| DbErr::Migration(e.to_string()))?
+        .returning_col(Modification::Id)
+        .to_owned();
+    let row = db
+        .query_one(db.get_database_backend().build(&insert))
+        .await?
+        .ok_or(DbErr::RecordNotInserted)?;
+    row.try_get::<i32>("", "id")
+}
+
+/// Runs know which file they installed, the revisions they point to are
+/// copied to that file when they belong to another one.
+async fn repair_runs(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let query = Query::select()
+        .columns([
+            (Run::Table, Run::Id),
+            (Run::Table, Run::FileId),
+            (Run::Table, Run::ModificationId),
+        ])
+        .from(Run::Table)
+        .inner_join(
+            Modification::Table,
+            Expr::col((Run::Table, Run::ModificationId))
+                .equals((Modification::Table, Modification::Id)),
+        )
+        .and_where(
+            Expr::col((Run::Table, Run::FileId))
+                .ne(Expr::col((Modification::Table, Modification::FileId))),
+        )
+        .to_owned();
+
+    for row in query_all(manager, &query).await? {
+        let run_id = row.try_get::<i32>("", "id")?;
+        // the copy takes the date of the run to keep the history in order
+        let started_at = Query::select()
+            .column(Run::StartedAt)
+            .from(Run::Table)
+            .and_where(Expr::col(Run::Id).eq(run_id))
+            .to_owned();
+        let id = copy_modification(
+            manager,
+            row.try_get::<i32>("", "modification_id")?,
+            row.try_get::<i32>("", "file_id")?,
+            SimpleExpr::SubQuery(None, Box::new(started_at.into_sub_query_statement())),
+        )
+        .await?;
+        let update = Query::update()
+            .table(Run::Table)
+            .value(Run::ModificationId, id)
+            .and_where(Expr::col(Run::Id).eq(run_id))
+            .to_owned();
+        db.execute(db.get_database_backend().build(&update)).await?;
+    }
+    Ok(())
+}
+
+/// Files without any revision got the one of another file with the same
+/// content, the revision is copied back when the file is still unchanged.
+async fn repair_files(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let query = Query::select()
+        .columns([File::Id, File::Path])
+        .from(File::Table)
+        .and_where(
+            Expr::col(File::Id).not_in_subquery(
+                Query::select()
+                    .column(Modification::FileId)
+                    .from(Modification::Table)
+                    .to_owned(),
+            ),
+        )
+        .to_owned();
+
+    for file in query_all(manager, &query).await? {
+        let path = file.try_get::<String>("", "path")?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let query = Query::select()
+            .column(Modification::Id)
+            .from(Modification::Table)
+            .and_where(Expr::col(Modification::Content).eq(content))
+            .order_by(Modification::Timestamp, Order::Asc)
+            .limit(1)
+            .to_owned();
+        if let Some(row) = query_all(manager, &query).await?.first() {
+            copy_modification(
+                manager,
+                row.try_get::<i32>("", "id")?,
+                file.try_get::<i32>("", "id")?,
+                Expr::col(Modification::Timestamp).into(),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Links each revision to the previous revision of the same file.
+async fn rebuild_chains(manager: &SchemaManager<'_>) -> Result<(), DbErr> {
+    let db = manager.get_connection();
+    let query = Query::select()
+        .columns([Modification::Id, Modification::FileId])
+        .from(Modification::Table)
+        .order_by(Modification::FileId, Order::Asc)
+        .order_by(Modification::Timestamp, Order::Asc)
+        .order_by(Modification::Id, Order::Asc)
+        .to_owned();
+
+    let mut previous: Option<(i32, i32)> = None;
+    for row in query_all(manager, &query).await? {
+        let id = row.try_get::<i32>("", "id")?;
+        let file_id = row.try_get::<i32>("", "file_id")?;
+        let previous_id = previous
+            .filter(|(previous_file_id, _)| *previous_file_id == file_id)
+            .map(|(_, previous_id)| previous_id);
+        let update = Query::update()
+            .table(Modification::Table)
+            .value(Modification::PreviousId, previous_id)
+            .and_where(Expr::col(Modification::Id).eq(id))
+            .to_owned();
+        db.execute(db.get_database_backend().build(&update)).await?;
+        previous = Some((file_id, id));
+    }
+    Ok(())
+}
+
+#[derive(Iden)]
+enum File {
+    Table,
+    Id,
+    Path,
+}
+
+#[derive(Iden)]
+enum Modification {
+    Table,
+    Id,
+    Timestamp,
+    Hash,
+    FileId,
+    PreviousId,
+    Content,
+}
+
+#[derive(Iden)]
+enum Run {
+    Table,
+    Id,
+    FileId,
+    ModificationId,
+    StartedAt,
+}
+
+#[cfg(test)]
+mod tests {
+    use crosup_repo::{
+        file::FileRepo, modification::ModificationRepo, project::ProjectRepo, run::RunRepo,
+    };
+    use sea_orm_migration::sea_orm::{Database, DatabaseConnection};
+
+    use crate::Migrator;
+
+    use super::*;
+
+    async fn create_file(db: &DatabaseConnection, path: &str) -> Result<i32, DbErr> {
+        let project = ProjectRepo::new(db).create("localhost", "/home/me").await?;
+        Ok(FileRepo::new(db)
+            .create("localhost", "Crosfile.hcl", path, project.id)
+            .await?
+            .id)
+    }
+
+    #[async_std::test]
+    async fn test_create() -> Result<(), DbErr> {
+        let db = Database::connect("sqlite::memory:").await?;
+        Migrator::up(&db, None).await?;
+        let repo = ModificationRepo::new(&db);
+
+        // files with the same content have revisions of their own
+        let a = create_file(&db, "/home/me/a/Crosfile.hcl").await?;
+        let b = create_file(&db, "/home/me/b/Crosfile.hcl").await?;
+        let first = repo.create(a, "h1", "vim", None).await?;
+        let shared = repo.create(b, "h1", "vim", None).await?;
+        assert_ne!(first.id, shared.id);
+        assert_eq!(shared.file_id, b);
+        assert_eq!(repo.find_by_file_id(a).await?, vec![first.clone()]);
+
+        // every install is a revision, the message goes on the new one
+        let again = repo.create(a, "h1", "vim", Some("reinstall")).await?;
+        assert_ne!(again.id, first.id);
+        assert_eq!(again.previous_id, Some(first.id));
+        assert_eq!(again.message.as_deref(), Some("reinstall"));
+        assert_eq!(repo.find(first.id).await?, Some(first.clone()));
+
+        // going back to an older content is a revision of its own
+        let second = repo.create(a, "h2", "git", None).await?;
+        let third = repo.create(a, "h1", "vim", Some("revert")).await?;
+        assert_eq!(third.previous_id, Some(second.id));
+        assert_eq!(second.previous_id, Some(again.id));
+        assert_eq!(
+            repo.find_by_file_id(a)
+                .await?
+                .iter()
+                .map(|m| m.id)
+                .collect::<Vec<_>>(),
+            vec![third.id, second.id, again.id, first.id]
+        );
+        Ok(())
+    }
+
+    #[async_std::test]
+    async fn test_repair_shared_revisions() -> Result<(), DbErr> {
+        let dir = std::env::temp_dir().join(format!("crosup-repair-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let unchanged = dir.join("Crosfile.hcl");
+        std::fs::write(&unchanged, "vim").unwrap();
+
+        let db = Database::connect("sqlite::memory:").await?;
+        Migrator::up(&db, Some(2)).await?;
+
+        // revisions used to be unique by hash: b and c got the revision of
+        // a, b installed it
+        db.execute_unprepared(&format!(
+            r#"
+INSERT INTO file (id, name, path) VALUES
+    (1, 'Crosfile.hcl', '/home/me/a/Crosfile.hcl'),
+    (2, 'Crosfile.hcl', '/home/me/b/Crosfile.hcl'),
+    (3, 'Crosfile.hcl', '{}');
+INSERT INTO modification (id, timestamp, hash, file_id, previous_id, content) VALUES
+    (1, '2026-01-01 10:00:00', 'h1', 1, NULL, 'vim'),
+    (2, '2026-01-02 10:00:00', 'h2', 1, 1, 'git');
+INSERT INTO run (id, file_id, modification_id, host, status, started_at, duration_ms) VALUES
+    (1, 2, 1, 'localhost', 'success', '2026-01-03 10:00:00', 1000);
+"#,
+            unchanged.display()
+        ))
+        .await?;
+
+        Migrator::up(&db, None).await?;
+        std::fs::remove_dir_all(&dir).unwrap();
+        let repo = ModificationRepo::new(&db);
+
+        // the revisions of a are left as they were
+        let a = repo.find_by_file_id(1).await?;
+        assert_eq!(a.iter().map(|m| m.id).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(a[0].previous_id, Some(1));
+
+        // b gets a copy of the revision its run installed, at the date of the run
+        let b = repo.find_by_file_id(2).await?;
+        assert_eq!(b.len(), 1);
+        assert_eq!((b[0].hash.as_str(), b[0].content.as_str()), ("h1", "vim"));
+        assert_eq!(b[0].timestamp.to_string(), "2026-01-03 10:00:00");
+        assert_eq!(b[0].previous_id, None);
+        let runs = RunRepo::new(&db).find_by_file_id(2).await?;
+        assert_eq!(runs[0].modification_id, Some(b[0].id));
+
+        // c is still unchanged on disk and gets a copy of the revision
+        let c = repo.find_by_file_id(3).await?;
+        assert_eq!(c.len(), 1);
+        assert_eq!((c[0].hash.as_str(), c[0].file_id), ("h1", 3));
+
+        // the hash is no longer unique
+        repo.create(1, "h1", "vim", None).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use sea_orm_migration::{prelude::*, sea_orm::ConnectionTrait};
+
+/// Groups the tracked files (Crosfile, Inventory, ...) by the directory they
+/// live in, existing files are attached to the project of their directory.
+/// Files and projects are tracked per machine, two machines sharing a
+/// Postgres database can have a Crosfile at the same path without mixing up
+/// their histories. Existing rows get an empty host, they are attached to
+/// the first machine which records the same path.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Project::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Project::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Project::Path).string().not_null())
+                    .col(
+                        ColumnDef::new(Project::Host)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(Project::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".into()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // SQLite can't add a foreign key to an existing table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .add_column(ColumnDef::new(File::ProjectId).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .add_column(ColumnDef::new(File::Host).string().not_null().default(""))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_host_path")
+                    .table(Project::Table)
+                    .col(Project::Host)
+                    .col(Project::Path)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_file_host_path")
+                    .table(File::Table)
+                    .col(File::Host)
+                    .col(File::Path)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+        let files = db
+            .query_all(
+                builder.build(
+                    Query::select()
+                        .columns([File::Id, File::Path])
+                        .from(File::Table),
+                ),
+            )
+            .await?;
+        for file in files {
+            let path = file.try_get::<String>("", "path")?;
+            let directory = match Path::new(&path).parent() {
+                Some(directory) => directory.display().to_string(),
+                None => continue,
+            };
+            let query = Query::select()
+                .column(Project::Id)
+                .from(Project::Table)
+                .and_where(Expr::col(Project::Path).eq(directory.clone()))
+                .to_owned();
+            let project_id = match db.query_one(builder.build(&query)).await? {
+                Some(project) => project.try_get::<i32>("", "id")?,
+                None => {
+                    let insert = Query::insert()
+                        .into_table(Project::Table)
+                        .columns([Project::Path])
+                        .values_panic([directory.into()])
+                        .returning_col(Project::Id)
+                        .to_owned();
+                    db.query_one(builder.build(&insert))
+                        .await?
+                        .ok_or(DbErr::RecordNotInserted)?
+                        .try_get::<i32>("", "id")?
+                }
+            };
+            let update = Query::update()
+                .table(File::Table)
+                .value(File::ProjectId, project_id)
+                .and_where(Expr::col(File::Id).eq(file.try_get::<i32>("", "id")?))
+                .to_owned();
+            db.execute(builder.build(&update)).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_file_host_path")
+                    .table(File::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .drop_column(File::Host)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(File::Table)
+                    .drop_column(File::ProjectId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Project::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Project {
+    Table,
+    Id,
+    Path,
+    Host,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum File {
+    Table,
+    Id,
+    Path,
+    Host,
+    ProjectId,
+}
+
+#[cfg(test)]
+mod tests {
+    use crosup_repo::{file::FileRepo, project::ProjectRepo};
+    use sea_orm_migration::sea_orm::Database;
+
+    use crate::Migrator;
+
+    use super::*;
+
+    #[async_std::test]
+    async fn test_files_are_kept_per_host() -> Result<(), DbErr> {
+        let db = Database::connect("sqlite::memory:").await?;
+        Migrator::up(&db, None).await?;
+
+        let path = "/home/me/Crosfile.toml";
+        let projects = ProjectRepo::new(&db);
+        let files = FileRepo::new(&db);
+
+        let a = projects.create("a", "/home/me").await?;
+        let b = projects.create("b", "/home/me").await?;
+        assert_ne!(a.id, b.id);
+        assert_eq!(projects.create("a", "/home/me").await?.id, a.id);
+
+        let file_a = files.create("a", "Crosfile.toml", path, a.id).await?;
+        let file_b = files.create("b", "Crosfile.toml", path, b.id).await?;
+        assert_ne!(file_a.id, file_b.id);
+        assert_eq!(files.find_by_path("a", path).await?, Some(file_a));
+        assert_eq!(files.find_by_path("b", path).await?, Some(file_b));
+        assert_eq!(files.find_by_path("c", path).await?, None);
+
+        // rows recorded before the host was tracked go to the first machine
+        // recording the same path
+        let path = "/home/old/Crosfile.toml";
+        let legacy = projects.create("", "/home/old").await?;
+        let file = files.create("", "Crosfile.toml", path, legacy.id).await?;
+        assert_eq!(files.find_by_path("c", path).await?, Some(file.clone()));
+
+        let claimed = files.create("c", "Crosfile.toml", path, legacy.id).await?;
+        assert_eq!(claimed.id, file.id);
+        assert_eq!(claimed.host, "c");
+        assert_eq!(files.find_by_path("d", path).await?, None);
+        assert_eq!(projects.create("c", "/home/old").await?.id, legacy.id);
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+/// Optional message and comma-separated tags of a revision, e.g.
+/// `crosup install -m "add k8s tools" --tag stable`.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports a single column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Modification::Table)
+                    .add_column(ColumnDef::new(Modification::Message).text().null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Modification::Table)
+                    .add_column(ColumnDef::new(Modification::Tags).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Modification::Table)
+                    .drop_column(Modification::Tags)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Modification::Table)
+                    .drop_column(Modification::Message)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Modification {
+    Table,
+    Message,
+    Tags,
+}
@@ -1,17 +1,14 @@
-use std::fs;
-
-use sea_orm_migration::{
-    prelude::*,
-    sea_orm::{DbBackend, Statement},
-};
+use sea_orm_migration::prelude::*;
 
 #[async_std::main]
 async fn main() {
-    let home = std::env::var("HOME").unwrap();
-    let crosup_dir = format!("{}/.config/crosup", home);
-    fs::create_dir_all(&crosup_dir).unwrap();
-
-    let database_url = format!("sqlite:{}/modifications.sqlite3?mode=rwc", crosup_dir);
+    let database_url = match crosup_repo::database::database_url() {
+        Ok(url) => url,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     std::env::set_var("DATABASE_URL", database_url);
 
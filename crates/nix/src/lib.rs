@@ -82,6 +82,21 @@ pub fn remove_packages(file: &str, pkgs: Vec<String>) -> Result<String, Error> {
     Ok(output)
 }
 
+/// Returns the names of the packages listed in `home.packages`, without the
+/// `pkgs.` prefix. Expressions such as overrides are skipped.
+pub fn list_packages(file: &str) -> Result<Vec<String>, Error> {
+    let packages = nix_editor::read::readvalue(file, "home.packages")?;
+    Ok(packages
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .flat_map(|line| line.split_whitespace())
+        .map(|x| x.trim_matches(|c| c == '[' || c == ']'))
+        .filter_map(|x| x.strip_prefix("pkgs."))
+        .filter(|x| !x.is_empty() && !x.contains(['(', ')', '{']))
+        .map(|x| x.to_string())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +154,13 @@ mod tests {
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn test_list_packages() {
+        let home_nix = fs::read_to_string("tests/home-with-vim-git.nix").unwrap();
+        let packages = list_packages(&home_nix).unwrap();
+        assert_eq!(packages, vec!["hello", "vim", "git"]);
+    }
+
     #[test]
     fn test_remove_packages() {
         // open file tests/home-with-vim-git.nix
@@ -1,6 +1,7 @@
 use crosup_entity::file;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
 };
 
 pub struct FileRepo {
@@ -16,23 +17,56 @@ impl FileRepo {
         file::Entity::find_by_id(id).one(&self.db).await
     }
 
-    pub async fn find_by_path(&self, path: &str) -> Result<Option<file::Model>, DbErr> {
+    pub async fn find_all(&self) -> Result<Vec<file::Model>, DbErr> {
+        file::Entity::find()
+            .order_by_asc(file::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    /// Finds the file recorded at `path` on `host`, or a file recorded
+    /// there before the host was tracked.
+    pub async fn find_by_path(&self, host: &str, path: &str) -> Result<Option<file::Model>, DbErr> {
         file::Entity::find()
             .filter(file::Column::Path.eq(path))
+            .filter(file::Column::Host.is_in([host, ""]))
+            .order_by_desc(file::Column::Host)
             .one(&self.db)
             .await
     }
 
-    pub async fn create(&self, name: &str, path: &str) -> Result<file::Model, DbErr> {
-        let result = self.find_by_path(path).await?;
+    pub async fn find_by_project_id(&self, project_id: i32) -> Result<Vec<file::Model>, DbErr> {
+        file::Entity::find()
+            .filter(file::Column::ProjectId.eq(project_id))
+            .order_by_asc(file::Column::Name)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        host: &str,
+        name: &str,
+        path: &str,
+        project_id: i32,
+    ) -> Result<file::Model, DbErr> {
+        let result = self.find_by_path(host, path).await?;
 
         if let Some(file) = result {
-            return Ok(file);
+            if file.project_id == Some(project_id) && file.host == host {
+                return Ok(file);
+            }
+            let mut file: file::ActiveModel = file.into();
+            file.project_id = Set(Some(project_id));
+            file.host = Set(host.to_owned());
+            return file.update(&self.db).await;
         }
 
         file::ActiveModel {
             name: Set(name.to_owned()),
             path: Set(path.to_owned()),
+            host: Set(host.to_owned()),
+            project_id: Set(Some(project_id)),
             ..Default::default()
         }
         .insert(&self.db)
@@ -1,2 +1,6 @@
+pub mod database;
 pub mod file;
 pub mod modification;
+pub mod project;
+pub mod run;
+pub mod run_step;
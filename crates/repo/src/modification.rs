@@ -1,7 +1,7 @@
 use crosup_entity::modification;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
-    Set,
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
 };
 
 pub struct ModificationRepo {
@@ -17,9 +17,25 @@ impl ModificationRepo {
         modification::Entity::find_by_id(id).one(&self.db).await
     }
 
-    pub async fn find_by_hash(&self, hash: &str) -> Result<Option<modification::Model>, DbErr> {
+    pub async fn find_all(&self) -> Result<Vec<modification::Model>, DbErr> {
         modification::Entity::find()
+            .order_by_asc(modification::Column::FileId)
+            .order_by_asc(modification::Column::Timestamp)
+            .order_by_asc(modification::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_by_hash(
+        &self,
+        file_id: i32,
+        hash: &str,
+    ) -> Result<Option<modification::Model>, DbErr> {
+        modification::Entity::find()
+            .filter(modification::Column::FileId.eq(file_id))
             .filter(modification::Column::Hash.eq(hash))
+            .order_by_desc(modification::Column::Timestamp)
+            .order_by_desc(modification::Column::Id)
             .one(&self.db)
             .await
     }
@@ -28,6 +44,7 @@ impl ModificationRepo {
         modification::Entity::find()
             .filter(modification::Column::FileId.eq(file_id))
             .order_by_desc(modification::Column::Timestamp)
+            .order_by_desc(modification::Column::Id)
             .all(&self.db)
             .await
     }
@@ -39,33 +56,143 @@ impl ModificationRepo {
         modification::Entity::find()
             .filter(modification::Column::FileId.eq(file_id))
             .order_by_desc(modification::Column::Timestamp)
+            .order_by_desc(modification::Column::Id)
             .one(&self.db)
             .await
     }
 
+    /// Records a new revision of the file, on every install even when the
+    /// content is the one of the last revision, so that each install has a
+    /// revision of its own to go back to.
     pub async fn create(
         &self,
         file_id: i32,
         hash: &str,
         content: &str,
+        message: Option<&str>,
     ) -> Result<modification::Model, DbErr> {
-        let result = self.find_by_hash(hash).await?;
-
-        if result.is_some() {
-            return Ok(result.unwrap());
-        }
-
         let last_modification = self.find_last_by_file_id(file_id).await?;
-        let previous_id = last_modification.map(|m| m.id);
 
         modification::ActiveModel {
             file_id: Set(file_id),
             hash: Set(hash.to_owned()),
             content: Set(content.to_owned()),
-            previous_id: Set(previous_id),
+            previous_id: Set(last_modification.map(|m| m.id)),
+            message: Set(message.map(|m| m.to_owned())),
             ..Default::default()
         }
         .insert(&self.db)
         .await
     }
+
+    /// Inserts a revision with its original timestamp, e.g. from an
+    /// exported history, `relink` has to be called afterwards.
+    pub async fn insert(
+        &self,
+        modification: modification::Model,
+    ) -> Result<modification::Model, DbErr> {
+        modification::ActiveModel {
+            timestamp: Set(modification.timestamp),
+            hash: Set(modification.hash),
+            file_id: Set(modification.file_id),
+            content: Set(modification.content),
+            message: Set(modification.message),
+            tags: Set(modification.tags),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// Adds a tag to a revision, a tag names a single revision of a file.
+    pub async fn add_tag(
+        &self,
+        modification: modification::Model,
+        tag: &str,
+    ) -> Result<modification::Model, DbErr> {
+        let tagged = self
+            .find_by_file_id(modification.file_id)
+            .await?
+            .into_iter()
+            .find(|m| tags(m).contains(&tag));
+        match tagged {
+            Some(m) if m.id == modification.id => return Ok(m),
+            Some(m) => {
+                return Err(DbErr::Custom(format!(
+                    "Tag {} already exists on revision {}",
+                    tag, m.hash
+                )))
+            }
+            None => {}
+        }
+
+        let mut names = tags(&modification);
+        names.push(tag);
+        let names = names.join(",");
+        let mut modification: modification::ActiveModel = modification.into();
+        modification.tags = Set(Some(names));
+        modification.update(&self.db).await
+    }
+
+    pub async fn remove_tag(
+        &self,
+        modification: modification::Model,
+        tag: &str,
+    ) -> Result<modification::Model, DbErr> {
+        let names: Vec<&str> = tags(&modification)
+            .into_iter()
+            .filter(|x| *x != tag)
+            .collect();
+        let names = Some(names.join(",")).filter(|x| !x.is_empty());
+        let mut modification: modification::ActiveModel = modification.into();
+        modification.tags = Set(names);
+        modification.update(&self.db).await
+    }
+
+    /// Links each revision of a file to the one before it.
+    pub async fn relink(&self, file_id: i32) -> Result<(), DbErr> {
+        let mut modifications = self.find_by_file_id(file_id).await?;
+        modifications.reverse();
+
+        let mut previous_id = None;
+        for modification in modifications {
+            let id = modification.id;
+            if modification.previous_id != previous_id {
+                let mut modification: modification::ActiveModel = modification.into();
+                modification.previous_id = Set(previous_id);
+                modification.update(&self.db).await?;
+            }
+            previous_id = Some(id);
+        }
+        Ok(())
+    }
+
+    /// Deletes revisions, the revisions which came after them no longer
+    /// have a previous one.
+    pub async fn delete(&self, ids: Vec<i32>) -> Result<u64, DbErr> {
+        modification::Entity::update_many()
+            .col_expr(
+                modification::Column::PreviousId,
+                Expr::value(Option::<i32>::None),
+            )
+            .filter(modification::Column::PreviousId.is_in(ids.clone()))
+            .exec(&self.db)
+            .await?;
+        let result = modification::Entity::delete_many()
+            .filter(modification::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
+
+/// Tags of a revision, e.g. `["stable", "v1"]`.
+pub fn tags(modification: &modification::Model) -> Vec<&str> {
+    modification
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|x| !x.is_empty())
+        .collect()
 }
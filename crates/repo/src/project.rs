@@ -0,0 +1,62 @@
+use crosup_entity::project;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+
+pub struct ProjectRepo {
+    db: DatabaseConnection,
+}
+
+impl ProjectRepo {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub async fn find(&self, id: i32) -> Result<Option<project::Model>, DbErr> {
+        project::Entity::find_by_id(id).one(&self.db).await
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<project::Model>, DbErr> {
+        project::Entity::find()
+            .order_by_asc(project::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    /// Finds the project of the directory `path` on `host`, or one recorded
+    /// there before the host was tracked.
+    pub async fn find_by_path(
+        &self,
+        host: &str,
+        path: &str,
+    ) -> Result<Option<project::Model>, DbErr> {
+        project::Entity::find()
+            .filter(project::Column::Path.eq(path))
+            .filter(project::Column::Host.is_in([host, ""]))
+            .order_by_desc(project::Column::Host)
+            .one(&self.db)
+            .await
+    }
+
+    pub async fn create(&self, host: &str, path: &str) -> Result<project::Model, DbErr> {
+        let result = self.find_by_path(host, path).await?;
+
+        if let Some(project) = result {
+            if project.host == host {
+                return Ok(project);
+            }
+            let mut project: project::ActiveModel = project.into();
+            project.host = Set(host.to_owned());
+            return project.update(&self.db).await;
+        }
+
+        project::ActiveModel {
+            path: Set(path.to_owned()),
+            host: Set(host.to_owned()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+    }
+}
@@ -0,0 +1,89 @@
+use crosup_entity::run;
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+
+pub struct RunRepo {
+    db: DatabaseConnection,
+}
+
+impl RunRepo {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub async fn find(&self, id: i32) -> Result<Option<run::Model>, DbErr> {
+        run::Entity::find_by_id(id).one(&self.db).await
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<run::Model>, DbErr> {
+        run::Entity::find()
+            .order_by_asc(run::Column::StartedAt)
+            .order_by_asc(run::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_older_than(
+        &self,
+        date: chrono::NaiveDateTime,
+    ) -> Result<Vec<run::Model>, DbErr> {
+        run::Entity::find()
+            .filter(run::Column::StartedAt.lt(date))
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn find_by_file_id(&self, file_id: i32) -> Result<Vec<run::Model>, DbErr> {
+        run::Entity::find()
+            .filter(run::Column::FileId.eq(file_id))
+            .order_by_desc(run::Column::StartedAt)
+            .order_by_desc(run::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        file_id: i32,
+        modification_id: Option<i32>,
+        host: &str,
+        status: &str,
+        started_at: chrono::NaiveDateTime,
+        duration_ms: i64,
+    ) -> Result<run::Model, DbErr> {
+        run::ActiveModel {
+            file_id: Set(file_id),
+            modification_id: Set(modification_id),
+            host: Set(host.to_owned()),
+            status: Set(status.to_owned()),
+            started_at: Set(started_at),
+            duration_ms: Set(duration_ms),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    /// Runs keep their record when the revision they installed is deleted.
+    pub async fn detach_modifications(&self, ids: Vec<i32>) -> Result<(), DbErr> {
+        run::Entity::update_many()
+            .col_expr(
+                run::Column::ModificationId,
+                Expr::value(Option::<i32>::None),
+            )
+            .filter(run::Column::ModificationId.is_in(ids))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, ids: Vec<i32>) -> Result<u64, DbErr> {
+        let result = run::Entity::delete_many()
+            .filter(run::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -0,0 +1,55 @@
+use crosup_entity::run_step;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set,
+};
+
+pub struct RunStepRepo {
+    db: DatabaseConnection,
+}
+
+impl RunStepRepo {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub async fn find_by_run_id(&self, run_id: i32) -> Result<Vec<run_step::Model>, DbErr> {
+        run_step::Entity::find()
+            .filter(run_step::Column::RunId.eq(run_id))
+            .order_by_asc(run_step::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    /// Inserts a step of a run, the `id` of the given model is ignored.
+    pub async fn create(&self, step: run_step::Model) -> Result<run_step::Model, DbErr> {
+        run_step::ActiveModel {
+            run_id: Set(step.run_id),
+            tool: Set(step.tool),
+            provider: Set(step.provider),
+            command: Set(step.command),
+            exit_status: Set(step.exit_status),
+            duration_ms: Set(step.duration_ms),
+            stdout: Set(step.stdout),
+            stderr: Set(step.stderr),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<run_step::Model>, DbErr> {
+        run_step::Entity::find()
+            .order_by_asc(run_step::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    pub async fn delete_by_run_ids(&self, run_ids: Vec<i32>) -> Result<u64, DbErr> {
+        let result = run_step::Entity::delete_many()
+            .filter(run_step::Column::RunId.is_in(run_ids))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
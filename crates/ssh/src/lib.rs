@@ -3,22 +3,55 @@ use owo_colors::OwoColorize;
 use ssh2::Session;
 use std::{
     collections::HashMap,
-    io::{self, Read},
+    io::{self, Read, Write},
+    path::Path,
     process::Command,
 };
 
 pub fn exec(sess: Session, command: &str) -> Result<(), Error> {
+    let (status, _) = run(sess, command)?;
+    check_status(command, status)
+}
+
+pub fn check_status(command: &str, status: i32) -> Result<(), Error> {
+    if status != 0 {
+        let command = command.bright_green();
+        return Err(Error::msg(format!(
+            "{} exit status is not 0, exit status = {}",
+            command, status
+        )));
+    }
+
+    Ok(())
+}
+
+/// Runs a command, printing its output as it comes, and returns its exit
+/// status with the output whatever the status is.
+pub fn run(sess: Session, command: &str) -> Result<(i32, String), Error> {
+    read_channel(sess, command, true)
+}
+
+/// Like `run`, without printing the output.
+pub fn run_with_output(sess: Session, command: &str) -> Result<(i32, String), Error> {
+    read_channel(sess, command, false)
+}
+
+fn read_channel(sess: Session, command: &str, echo: bool) -> Result<(i32, String), Error> {
     let mut channel = sess.channel_session()?;
 
     channel.exec(command)?;
 
+    let mut output = String::new();
     let mut buffer = [0; 1024];
     loop {
         match channel.read(&mut buffer) {
             Ok(n) => {
                 if n > 0 {
                     let chunk = String::from_utf8_lossy(&buffer[..n]);
-                    print!("{}", chunk);
+                    if echo {
+                        print!("{}", chunk);
+                    }
+                    output.push_str(&chunk);
                 } else {
                     break;
                 }
@@ -30,18 +63,60 @@ pub fn exec(sess: Session, command: &str) -> Result<(), Error> {
         }
     }
 
-    if channel.exit_status()? != 0 {
-        let command = command.bright_green();
-        return Err(Error::msg(format!(
-            "{} exit status is not 0, exit status = {}",
-            command,
-            channel.exit_status()?
-        )));
-    }
+    channel.wait_close()?;
+
+    Ok((channel.exit_status()?, output))
+}
 
+pub fn exec_with_output(sess: Session, command: &str) -> Result<String, Error> {
+    let (status, output) = run_with_output(sess, command)?;
+    check_status(command, status)?;
+    Ok(output)
+}
+
+/// Reads a remote file over SFTP, returns `None` if it doesn't exist.
+pub fn read_file(sess: Session, path: &str) -> Result<Option<Vec<u8>>, Error> {
+    let sftp = sess.sftp()?;
+    let mut file = match sftp.open(Path::new(path)) {
+        Ok(file) => file,
+        Err(_) => return Ok(None),
+    };
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    Ok(Some(content))
+}
+
+/// Writes a remote file over SFTP, creating or truncating it.
+pub fn write_file(sess: Session, path: &str, content: &[u8], mode: i32) -> Result<(), Error> {
+    let sftp = sess.sftp()?;
+    let mut file = sftp.create(Path::new(path))?;
+    file.write_all(content)?;
+    sftp.setstat(
+        Path::new(path),
+        ssh2::FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: Some(mode as u32),
+            atime: None,
+            mtime: None,
+        },
+    )?;
     Ok(())
 }
 
+/// Returns the target of a remote symlink, `None` if the path isn't a symlink.
+pub fn read_link(sess: Session, path: &str) -> Result<Option<String>, Error> {
+    let sftp = sess.sftp()?;
+    match sftp.lstat(Path::new(path)) {
+        Ok(stat) if stat.file_type().is_symlink() => {
+            let target = sftp.readlink(Path::new(path))?;
+            Ok(Some(target.to_string_lossy().to_string()))
+        }
+        _ => Ok(None),
+    }
+}
+
 pub fn setup_ssh_agent_var() -> Result<(), Error> {
     println!("-> Setting up ssh-agent {}", "ssh-agent -s".bright_green());
     let child = Command::new("ssh-agent").arg("-s").output()?;
@@ -0,0 +1,73 @@
+use similar::{ChangeTag, TextDiff};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+};
+
+/// Lines of a unified diff colored like `crosup diff`: line numbers of the
+/// current content, removed lines in red, added ones in green and the
+/// changed words underlined.
+pub fn diff_lines(previous: &str, current: &str) -> Vec<Spans<'static>> {
+    let diff = TextDiff::from_lines(previous, current);
+    let mut lines = vec![];
+
+    for (idx, group) in diff.grouped_ops(3).iter().enumerate() {
+        if idx > 0 {
+            lines.push(Spans::from("-".repeat(80)));
+        }
+        for op in group {
+            for change in diff.iter_inline_changes(op) {
+                let (sign, style) = match change.tag() {
+                    ChangeTag::Delete => (
+                        "-",
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    ),
+                    ChangeTag::Insert => (
+                        "+",
+                        Style::default()
+                            .fg(Color::Green)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    ChangeTag::Equal => (" ", Style::default()),
+                };
+                let line = match change.new_index() {
+                    None => "    ".to_string(),
+                    Some(idx) => format!("{:>4}", idx + 1),
+                };
+                let mut spans = vec![Span::raw(format!("{}|", line)), Span::styled(sign, style)];
+                for (emphasized, value) in change.iter_strings_lossy() {
+                    let value = value.trim_end_matches('\n').to_string();
+                    match emphasized {
+                        true => spans.push(Span::styled(
+                            value,
+                            style.add_modifier(Modifier::UNDERLINED),
+                        )),
+                        false => spans.push(Span::styled(value, style)),
+                    }
+                }
+                lines.push(Spans::from(spans));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(spans: &Spans) -> String {
+        spans.0.iter().map(|x| x.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines("vim\ngit\n", "vim\nzig\n");
+        assert_eq!(
+            lines.iter().map(text).collect::<Vec<_>>(),
+            vec!["   1| vim", "    |-git", "   2|+zig"]
+        );
+        assert_eq!(lines[1].0[1].style.fg, Some(Color::Red));
+        assert_eq!(lines[2].0[1].style.fg, Some(Color::Green));
+    }
+}
@@ -1,23 +1,45 @@
 use anyhow::Error;
-use bat::PrettyPrinter;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use crossterm::{
-    event::{self, DisableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, LeaveAlternateScreen},
 };
-use std::{io, thread, time::Duration};
+use std::io::{self, Write};
 use tui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Terminal,
 };
 
-use crate::App;
+use crate::{diff::diff_lines, Action, App};
 
-pub fn display_history(mut app: App) -> Result<(), Error> {
+const HELP: &str = "↑/↓ select  PgUp/PgDn scroll  tab diff  space mark  / search  n next  y copy hash  r rollback  enter zoom  q quit";
+
+#[derive(PartialEq)]
+enum Mode {
+    Normal,
+    Search,
+    ConfirmRollback,
+}
+
+/// State of the history screen which isn't part of the data to display.
+struct View {
+    mode: Mode,
+    diff: bool,
+    zoom: bool,
+    scroll: u16,
+    /// Revision to compare the selected one with, instead of the one
+    /// before it.
+    marked: Option<usize>,
+    query: String,
+    status: Option<String>,
+}
+
+pub fn display_history(mut app: App) -> Result<Action, Error> {
     enable_raw_mode()?;
     let stdout = io::stdout();
 
@@ -25,49 +47,174 @@ pub fn display_history(mut app: App) -> Result<(), Error> {
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
 
-    render_initial_ui(&mut terminal, &mut app)?;
+    let mut view = View {
+        mode: Mode::Normal,
+        diff: false,
+        zoom: false,
+        scroll: 0,
+        marked: None,
+        query: String::new(),
+        status: app.status.take(),
+    };
 
     loop {
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') => break,
-                KeyCode::Up => {
-                    if app.selected_index > 0 {
-                        app.selected_index -= 1;
-                    }
+        render_ui(&mut terminal, &app, &view)?;
+
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            _ => continue,
+        };
+
+        match view.mode {
+            Mode::Search => handle_search(key, &mut app, &mut view),
+            Mode::ConfirmRollback => {
+                if let KeyCode::Char('y') | KeyCode::Char('Y') = key.code {
+                    clear_screen(&mut terminal)?;
+                    return Ok(Action::Rollback(app.selected_index));
                 }
-                KeyCode::Down => {
-                    if app.selected_index < app.items.len() - 1 {
-                        app.selected_index += 1;
+                view.mode = Mode::Normal;
+                view.status = None;
+            }
+            Mode::Normal => {
+                view.status = None;
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Up | KeyCode::Char('k') => select(&mut app, &mut view, -1),
+                    KeyCode::Down | KeyCode::Char('j') => select(&mut app, &mut view, 1),
+                    KeyCode::Home | KeyCode::Char('g') => {
+                        let len = app.items.len() as isize;
+                        select(&mut app, &mut view, -len)
                     }
+                    KeyCode::End | KeyCode::Char('G') => {
+                        let len = app.items.len() as isize;
+                        select(&mut app, &mut view, len)
+                    }
+                    KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+                    KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        view.scroll = view.scroll.saturating_add(10)
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        view.scroll = view.scroll.saturating_sub(10)
+                    }
+                    KeyCode::Char('J') => view.scroll = view.scroll.saturating_add(1),
+                    KeyCode::Char('K') => view.scroll = view.scroll.saturating_sub(1),
+                    KeyCode::Tab | KeyCode::Char('d') if app.revisions => {
+                        view.diff = !view.diff;
+                        view.scroll = 0;
+                    }
+                    KeyCode::Char(' ') | KeyCode::Char('m') if app.revisions => {
+                        view.marked = match view.marked {
+                            Some(index) if index == app.selected_index => None,
+                            _ => Some(app.selected_index),
+                        };
+                        view.diff = view.marked.is_some() || view.diff;
+                        view.scroll = 0;
+                    }
+                    KeyCode::Enter => view.zoom = !view.zoom,
+                    KeyCode::Char('/') => {
+                        view.mode = Mode::Search;
+                        view.query.clear();
+                    }
+                    KeyCode::Char('n') => search(&mut app, &mut view, 1),
+                    KeyCode::Char('N') => search(&mut app, &mut view, -1),
+                    KeyCode::Char('y') => {
+                        view.status = match app.hashes.get(&app.selected_index) {
+                            Some(hash) => {
+                                copy_to_clipboard(hash)?;
+                                Some(format!("Copied {} to the clipboard", hash))
+                            }
+                            None => Some("No revision to copy".into()),
+                        };
+                    }
+                    KeyCode::Char('r') => match app.hashes.get(&app.selected_index) {
+                        Some(hash) => {
+                            view.mode = Mode::ConfirmRollback;
+                            view.scroll = 0;
+                            view.status = Some(format!(
+                                "Roll back to revision {}? [y/N]",
+                                hash.get(..8).unwrap_or(hash)
+                            ));
+                        }
+                        None => view.status = Some("No revision to roll back to".into()),
+                    },
+                    _ => {}
                 }
-                KeyCode::Enter => {
-                    let content = app.content.get(&app.selected_index).unwrap();
-                    clear_screen(&mut terminal)?;
-                    let language = match app.title.ends_with(".hcl") {
-                        true => "hcl",
-                        false => "toml",
-                    };
-
-                    PrettyPrinter::new()
-                        .input_from_bytes(content.as_bytes())
-                        .language(language)
-                        .paging_mode(bat::PagingMode::Always)
-                        .line_numbers(true)
-                        .print()
-                        .unwrap();
-                    return Ok(());
-                }
-                _ => {}
             }
         }
-
-        render_ui(&mut terminal, &mut app)?;
-        thread::sleep(Duration::from_millis(10));
     }
 
     clear_screen(&mut terminal)?;
 
+    Ok(Action::None)
+}
+
+fn handle_search(key: KeyEvent, app: &mut App, view: &mut View) {
+    match key.code {
+        KeyCode::Esc => {
+            view.mode = Mode::Normal;
+            view.query.clear();
+        }
+        KeyCode::Enter => {
+            view.mode = Mode::Normal;
+            search(app, view, 0);
+        }
+        KeyCode::Backspace => {
+            view.query.pop();
+        }
+        KeyCode::Char(c) => view.query.push(c),
+        _ => {}
+    }
+}
+
+fn select(app: &mut App, view: &mut View, offset: isize) {
+    if app.items.is_empty() {
+        return;
+    }
+    let index = (app.selected_index as isize + offset).clamp(0, app.items.len() as isize - 1);
+    if index as usize != app.selected_index {
+        app.selected_index = index as usize;
+        view.scroll = 0;
+    }
+}
+
+/// Selects the next item (or the previous one when `direction` is
+/// negative) whose line or content contains the query, starting from the
+/// selected item when `direction` is 0.
+fn search(app: &mut App, view: &mut View, direction: isize) {
+    if view.query.is_empty() || app.items.is_empty() {
+        return;
+    }
+    let query = view.query.to_lowercase();
+    let len = app.items.len() as isize;
+    let step = if direction < 0 { -1 } else { 1 };
+    let found = (0..len)
+        .map(|i| (app.selected_index as isize + direction + i * step).rem_euclid(len) as usize)
+        .find(|&i| {
+            app.items[i].to_lowercase().contains(&query)
+                || app
+                    .content
+                    .get(&i)
+                    .map(|x| x.to_lowercase().contains(&query))
+                    .unwrap_or(false)
+        });
+    match found {
+        Some(index) => {
+            app.selected_index = index;
+            view.scroll = 0;
+            view.status = Some(format!("/{}", view.query));
+        }
+        None => view.status = Some(format!("No match for {}", view.query)),
+    }
+}
+
+/// Copies to the clipboard of the terminal with the OSC 52 escape sequence,
+/// which also works over ssh.
+fn copy_to_clipboard(text: &str) -> Result<(), Error> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()?;
     Ok(())
 }
 
@@ -79,64 +226,243 @@ pub fn clear_screen(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Re
     Ok(())
 }
 
-pub fn render_initial_ui(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut App,
-) -> Result<(), Error> {
-    terminal.draw(|f| {
-        let size = f.size();
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-            .split(size);
+/// Content of the preview pane, the selected revision or its diff with the
+/// marked revision (or the one before it), or the changes a rollback to the
+/// selected revision would make while it is being confirmed.
+fn preview(app: &App, view: &View) -> (String, Vec<Spans<'static>>) {
+    let index = app.selected_index;
+    let empty = String::new();
+    let content = app.content.get(&index).unwrap_or(&empty);
 
-        let items: Vec<ListItem> = app
-            .items
-            .iter()
+    if view.mode == Mode::ConfirmRollback {
+        let target = app.targets.get(&index).unwrap_or(content);
+        let current = app
+            .current
+            .as_ref()
+            .or(app.content.get(&0))
+            .unwrap_or(&empty);
+        let hash = app.hashes.get(&index).unwrap_or(&empty);
+        let mut lines = diff_lines(current, target);
+        if lines.is_empty() {
+            lines.push(Spans::from("No changes"));
+        }
+        return (
+            format!(
+                "Changes when rolling back to {}",
+                hash.get(..8).unwrap_or(hash)
+            ),
+            lines,
+        );
+    }
+
+    if !view.diff {
+        let lines = content
+            .lines()
             .enumerate()
-            .map(|(i, item)| {
-                let style = if i == app.selected_index {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                ListItem::new(Spans::from(Span::styled(item, style)))
-            })
+            .map(|(i, line)| Spans::from(format!("{:>4}| {}", i + 1, line)))
             .collect();
+        return (app.title.clone(), lines);
+    }
 
-        let list = List::new(items).block(tui::widgets::Block::default());
-        f.render_widget(list, chunks[1]);
-    })?;
-    Ok(())
+    let marked = view.marked.filter(|x| *x != index);
+    let other = marked.unwrap_or(index + 1);
+    let previous = app.content.get(&other).unwrap_or(&empty);
+    let title = match app.hashes.get(&other) {
+        Some(hash) if marked.is_some() => {
+            format!(
+                "Diff with marked revision {}",
+                hash.get(..8).unwrap_or(hash)
+            )
+        }
+        Some(hash) => format!(
+            "Diff with previous revision {}",
+            hash.get(..8).unwrap_or(hash)
+        ),
+        None => "Diff with an empty file".into(),
+    };
+    let mut lines = diff_lines(previous, content);
+    if lines.is_empty() {
+        lines.push(Spans::from("No changes"));
+    }
+    (title, lines)
 }
 
-pub fn render_ui(
+fn render_ui(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut App,
+    app: &App,
+    view: &View,
 ) -> Result<(), Error> {
     terminal.draw(|f| {
         let size = f.size();
-        let chunks = Layout::default()
+        let rows = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
             .split(size);
 
+        f.render_widget(
+            Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)),
+            rows[0],
+        );
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                match view.zoom {
+                    true => [Constraint::Percentage(0), Constraint::Percentage(100)],
+                    false => [Constraint::Percentage(40), Constraint::Percentage(60)],
+                }
+                .as_ref(),
+            )
+            .split(rows[1]);
+
         let items: Vec<ListItem> = app
             .items
             .iter()
             .enumerate()
             .map(|(i, item)| {
-                let style = if i == app.selected_index {
-                    Style::default().fg(Color::Yellow)
-                } else {
-                    Style::default().fg(Color::White)
+                let style = match view.marked == Some(i) {
+                    true => Style::default().fg(Color::Cyan),
+                    false => Style::default().fg(Color::White),
                 };
-                ListItem::new(Spans::from(Span::styled(item, style)))
+                let prefix = if view.marked == Some(i) { "* " } else { "  " };
+                ListItem::new(Spans::from(Span::styled(
+                    format!("{}{}", prefix, item),
+                    style,
+                )))
             })
             .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(app.title.clone()),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            );
+        let mut state = ListState::default();
+        state.select(Some(app.selected_index));
+        if !view.zoom {
+            f.render_stateful_widget(list, columns[0], &mut state);
+        }
+
+        let (title, lines) = preview(app, view);
+        let scroll = view
+            .scroll
+            .min(lines.len().saturating_sub(1).min(u16::MAX as usize) as u16);
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((scroll, 0));
+        f.render_widget(paragraph, columns[1]);
 
-        let list = List::new(items).block(Block::default());
-        f.render_widget(list, chunks[1]);
+        let status = match view.mode {
+            Mode::Search => format!("/{}", view.query),
+            _ => view.status.clone().unwrap_or_default(),
+        };
+        f.render_widget(
+            Paragraph::new(status).style(Style::default().fg(Color::Yellow)),
+            rows[2],
+        );
     })?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn app(revisions: &[&str], current: Option<&str>) -> App {
+        App {
+            items: revisions.iter().map(|x| x.to_string()).collect(),
+            content: revisions
+                .iter()
+                .enumerate()
+                .map(|(i, x)| (i, x.to_string()))
+                .collect(),
+            hashes: (0..revisions.len())
+                .map(|i| (i, format!("{}", i).repeat(10)))
+                .collect(),
+            selected_index: 0,
+            current: current.map(|x| x.into()),
+            targets: HashMap::new(),
+            title: "Crosfile.hcl".into(),
+            revisions: true,
+            status: None,
+        }
+    }
+
+    fn view(mode: Mode) -> View {
+        View {
+            mode,
+            diff: false,
+            zoom: false,
+            scroll: 0,
+            marked: None,
+            query: String::new(),
+            status: None,
+        }
+    }
+
+    fn text(lines: &[Spans]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.0.iter().map(|x| x.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_preview_rollback() {
+        let mut app = app(&["vim\n", "git\n"], Some("vim\nfish\n"));
+        app.selected_index = 1;
+
+        let (title, lines) = preview(&app, &view(Mode::ConfirmRollback));
+        assert_eq!(title, "Changes when rolling back to 11111111");
+        assert_eq!(text(&lines), text(&diff_lines("vim\nfish\n", "git\n")));
+
+        // the configuration of a run is restored instead of its report
+        app.targets.insert(1, "vim\nfish\n".into());
+        let (_, lines) = preview(&app, &view(Mode::ConfirmRollback));
+        assert_eq!(text(&lines), vec!["No changes"]);
+
+        let (title, _) = preview(&app, &view(Mode::Normal));
+        assert_eq!(title, "Crosfile.hcl");
+    }
+
+    #[test]
+    fn test_preview_diff() {
+        let mut app = app(&["vim\n", "git\n", "fish\n"], None);
+        let mut view = view(Mode::Normal);
+        view.diff = true;
+
+        let (title, lines) = preview(&app, &view);
+        assert_eq!(title, "Diff with previous revision 11111111");
+        assert_eq!(text(&lines), text(&diff_lines("git\n", "vim\n")));
+
+        // the marked revision is labelled as such even when it is the
+        // previous one
+        view.marked = Some(1);
+        let (title, _) = preview(&app, &view);
+        assert_eq!(title, "Diff with marked revision 11111111");
+
+        view.marked = Some(2);
+        let (title, lines) = preview(&app, &view);
+        assert_eq!(title, "Diff with marked revision 22222222");
+        assert_eq!(text(&lines), text(&diff_lines("fish\n", "vim\n")));
+
+        // the marked revision itself is compared with the previous one
+        app.selected_index = 2;
+        let (title, _) = preview(&app, &view);
+        assert_eq!(title, "Diff with an empty file");
+    }
+}
@@ -1,10 +1,33 @@
 use std::collections::HashMap;
 
+pub mod diff;
 pub mod history;
+pub mod picker;
 
 pub struct App {
     pub items: Vec<String>,
     pub content: HashMap<usize, String>,
+    /// Hash of the revision of each item, copied with `y`.
+    pub hashes: HashMap<usize, String>,
     pub selected_index: usize,
+    /// Content of the file on disk, the changes a rollback would make are
+    /// shown before confirming it.
+    pub current: Option<String>,
+    /// Content restored by a rollback from an item, when it isn't the
+    /// content of the item itself, e.g. the configuration of a run.
+    pub targets: HashMap<usize, String>,
     pub title: String,
+    /// Items are revisions of a single file, sorted from the latest to the
+    /// oldest, which can be compared with each other.
+    pub revisions: bool,
+    /// Message shown in the status bar when the view opens, e.g. after a
+    /// rollback.
+    pub status: Option<String>,
+}
+
+/// What the user asked for when leaving the history screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    None,
+    Rollback(usize),
 }
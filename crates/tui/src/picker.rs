@@ -0,0 +1,214 @@
+use anyhow::Error;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyModifiers},
+    terminal::enable_raw_mode,
+};
+use std::io;
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Terminal,
+};
+
+use crate::history::clear_screen;
+
+const HELP: &str =
+    "↑/↓ select  space toggle  a toggle all  enter install the selected tools  q quit";
+
+/// A vertex of the installer graph.
+pub struct Tool {
+    pub name: String,
+    pub provider: String,
+    pub dependencies: Vec<String>,
+    /// `None` when it can't be checked, e.g. on remote machines.
+    pub installed: Option<bool>,
+}
+
+/// Lets the user pick the tools to install, all of them are selected at
+/// first. Returns the names of the selected tools, or `None` if the user
+/// quit without installing anything.
+pub fn pick_tools(title: &str, tools: &[Tool]) -> Result<Option<Vec<String>>, Error> {
+    enable_raw_mode()?;
+    let stdout = io::stdout();
+
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.clear()?;
+
+    let mut selected = vec![true; tools.len()];
+    let mut index = 0;
+
+    loop {
+        render_ui(&mut terminal, title, tools, &selected, index)?;
+
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            _ => continue,
+        };
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Up | KeyCode::Char('k') => index = index.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                index = (index + 1).min(tools.len().saturating_sub(1))
+            }
+            KeyCode::Char(' ') if !tools.is_empty() => toggle(tools, &mut selected, index),
+            KeyCode::Char('a') => {
+                let all = !selected.iter().all(|x| *x);
+                selected.iter_mut().for_each(|x| *x = all);
+            }
+            KeyCode::Enter => {
+                clear_screen(&mut terminal)?;
+                return Ok(Some(
+                    tools
+                        .iter()
+                        .zip(selected)
+                        .filter(|(_, selected)| *selected)
+                        .map(|(tool, _)| tool.name.clone())
+                        .collect(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    clear_screen(&mut terminal)?;
+    Ok(None)
+}
+
+/// Selects a tool along with its dependencies, or unselects it along with
+/// the tools depending on it.
+fn toggle(tools: &[Tool], selected: &mut [bool], index: usize) {
+    let value = !selected[index];
+    let mut stack = vec![index];
+
+    while let Some(i) = stack.pop() {
+        selected[i] = value;
+        let next: Vec<usize> = match value {
+            true => tools[i]
+                .dependencies
+                .iter()
+                .filter_map(|dependency| tools.iter().position(|x| x.name == *dependency))
+                .collect(),
+            false => tools
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| x.dependencies.contains(&tools[i].name))
+                .map(|(j, _)| j)
+                .collect(),
+        };
+        stack.extend(next.into_iter().filter(|j| selected[*j] != value));
+    }
+}
+
+fn render_ui(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    title: &str,
+    tools: &[Tool],
+    selected: &[bool],
+    index: usize,
+) -> Result<(), Error> {
+    terminal.draw(|f| {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(f.size());
+
+        f.render_widget(
+            Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)),
+            rows[0],
+        );
+
+        let header = Row::new(vec!["", "Tool", "Provider", "Dependencies", "Status"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let items: Vec<Row> = tools
+            .iter()
+            .zip(selected)
+            .map(|(tool, selected)| {
+                let (status, color) = match tool.installed {
+                    Some(true) => ("installed", Color::Green),
+                    Some(false) => ("not installed", Color::White),
+                    None => ("unknown", Color::DarkGray),
+                };
+                Row::new(vec![
+                    Cell::from(if *selected { "[x]" } else { "[ ]" }),
+                    Cell::from(tool.name.clone()),
+                    Cell::from(tool.provider.clone()),
+                    Cell::from(tool.dependencies.join(", ")),
+                    Cell::from(status).style(Style::default().fg(color)),
+                ])
+            })
+            .collect();
+        let table = Table::new(items)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .widths(&[
+                Constraint::Length(3),
+                Constraint::Percentage(30),
+                Constraint::Percentage(15),
+                Constraint::Percentage(35),
+                Constraint::Percentage(15),
+            ]);
+        let mut state = TableState::default();
+        state.select(Some(index));
+        f.render_stateful_widget(table, rows[1], &mut state);
+
+        let count = selected.iter().filter(|x| **x).count();
+        f.render_widget(
+            Paragraph::new(format!("{} of {} tools selected", count, tools.len()))
+                .style(Style::default().fg(Color::Yellow)),
+            rows[2],
+        );
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, dependencies: &[&str]) -> Tool {
+        Tool {
+            name: name.into(),
+            provider: "nix".into(),
+            dependencies: dependencies.iter().map(|x| x.to_string()).collect(),
+            installed: None,
+        }
+    }
+
+    #[test]
+    fn test_toggle() {
+        let tools = vec![
+            tool("nix", &[]),
+            tool("devbox", &["nix"]),
+            tool("fish", &["devbox"]),
+            tool("vim", &[]),
+        ];
+
+        let mut selected = vec![false; 4];
+        toggle(&tools, &mut selected, 2);
+        assert_eq!(selected, vec![true, true, true, false]);
+
+        toggle(&tools, &mut selected, 0);
+        assert_eq!(selected, vec![false, false, false, false]);
+
+        let mut selected = vec![true; 4];
+        toggle(&tools, &mut selected, 1);
+        assert_eq!(selected, vec![true, false, false, true]);
+    }
+}
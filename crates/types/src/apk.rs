@@ -26,4 +26,7 @@ pub struct Package {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interactive: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
 }
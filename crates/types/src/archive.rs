@@ -0,0 +1,43 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ArchiveConfiguration {
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub pkg: IndexMap<String, Package>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Package {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    pub url: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strip_components: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postinstall: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_check: Option<String>,
+}
@@ -1,5 +1,4 @@
 use indexmap::IndexMap;
-use os_release::OsRelease;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -12,6 +11,24 @@ pub struct BrewConfiguration {
         serialize_with = "hcl::ser::labeled_block"
     )]
     pub pkg: Option<IndexMap<String, Package>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub tap: Option<IndexMap<String, Tap>>,
+}
+
+/// Third-party repository, e.g. `tap "hashicorp/tap" {}`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Tap {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    /// Git URL of the tap, when it isn't hosted on GitHub as
+    /// `<user>/homebrew-<repo>`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -22,13 +39,33 @@ pub struct Package {
     pub preinstall: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postinstall: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_check: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cask: Option<bool>,
+
+    /// Extra options passed to `brew install`, e.g. `["--with-openssl"]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+
+    /// Build from the latest commit of the repository (`brew install --HEAD`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<bool>,
+
+    /// Prevent `brew upgrade` from upgrading the formula (`brew pin`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pin: Option<bool>,
+
+    /// Tap the formula comes from, inferred from names such as
+    /// `hashicorp/tap/terraform`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tap: Option<String>,
 }
 
-pub fn default_brew_install() -> IndexMap<String, BrewConfiguration> {
+pub fn default_brew_install(family: &str) -> IndexMap<String, BrewConfiguration> {
     let mut brew = IndexMap::new();
     let mut pkg = IndexMap::new();
     let mut minikube = super::brew::Package {
@@ -38,28 +75,17 @@ pub fn default_brew_install() -> IndexMap<String, BrewConfiguration> {
         ..Default::default()
     };
 
-    if cfg!(target_os = "linux") {
-        // determine linux distribution using os-release
-        if let Ok(os_release) = OsRelease::new() {
-            let os = os_release.id.to_lowercase();
-            let os = os.as_str();
-            match os {
-                "ubuntu" | "debian" | "linuxmint" | "pop" | "elementary" | "zorin" => {
-                    minikube.preinstall = Some(
-                        "sudo apt-get install -y qemu-system libvirt-clients libvirt-daemon-system"
-                            .into(),
-                    );
-                    minikube.postinstall = Some(
-                        r#"sudo sed -i 's/#user = "root"/user = "root"/g' /etc/libvirt/qemu.conf
+    if family == "debian" {
+        minikube.preinstall = Some(
+            "sudo apt-get install -y qemu-system libvirt-clients libvirt-daemon-system".into(),
+        );
+        minikube.postinstall = Some(
+            r#"sudo sed -i 's/#user = "root"/user = "root"/g' /etc/libvirt/qemu.conf
             sudo sed -i 's/#group = "root"/group = "root"/g' /etc/libvirt/qemu.conf
             sudo sed -i 's/#dynamic_ownership = 1/dynamic_ownership = 0/g' /etc/libvirt/qemu.conf
             sudo sed -i 's/#remember_owner = 1/remember_owner = 0/g' /etc/libvirt/qemu.conf"#
-                            .into(),
-                    );
-                }
-                _ => {}
-            }
-        }
+                .into(),
+        );
     }
 
     pkg.insert("minikube".into(), minikube);
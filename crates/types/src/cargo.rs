@@ -0,0 +1,56 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CargoConfiguration {
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub pkg: IndexMap<String, Package>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Package {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_features: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_default_features: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bins: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postinstall: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_check: Option<String>,
+}
@@ -6,15 +6,27 @@ use crate::fleek::FleekConfiguration;
 use super::{
     apk::ApkConfiguration,
     apt::{default_apt_install, AptConfiguration},
+    archive::ArchiveConfiguration,
     brew::{default_brew_install, BrewConfiguration},
+    cargo::CargoConfiguration,
     curl::{default_curl_install, CurlConfiguration},
     dnf::DnfConfiguration,
     emerge::EmergeConfiguration,
+    file::FileConfiguration,
+    flatpak::FlatpakConfiguration,
     git::{default_git_install, GitConfiguration},
+    go::GoConfiguration,
     install::InstallConfiguration,
     nix::{default_nix_install, NixConfiguration},
+    npm::NpmConfiguration,
     pacman::PacmanConfiguration,
+    pipx::PipxConfiguration,
+    release::ReleaseConfiguration,
+    rpm_ostree::RpmOstreeConfiguration,
+    runtime::RuntimeConfiguration,
+    service::{default_service_install, ServiceConfiguration},
     slackpkg::SlackpkgConfiguration,
+    xbps::XbpsConfiguration,
     yum::YumConfiguration,
     zypper::ZypperConfiguration,
 };
@@ -33,6 +45,12 @@ pub struct Configuration {
         serialize_with = "hcl::ser::block"
     )]
     pub install: Option<InstallConfiguration>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub distro: Option<IndexMap<String, String>>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "hcl::ser::labeled_block"
@@ -105,23 +123,98 @@ pub struct Configuration {
     )]
     pub slackpkg: Option<IndexMap<String, SlackpkgConfiguration>>,
 
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub xbps: Option<IndexMap<String, XbpsConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub rpm_ostree: Option<IndexMap<String, RpmOstreeConfiguration>>,
+
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "hcl::ser::labeled_block"
     )]
     pub fleek: Option<IndexMap<String, FleekConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub cargo: Option<IndexMap<String, CargoConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub go: Option<IndexMap<String, GoConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub pipx: Option<IndexMap<String, PipxConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub npm: Option<IndexMap<String, NpmConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub release: Option<IndexMap<String, ReleaseConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub archive: Option<IndexMap<String, ArchiveConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub runtime: Option<IndexMap<String, RuntimeConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub file: Option<IndexMap<String, FileConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub service: Option<IndexMap<String, ServiceConfiguration>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::labeled_block"
+    )]
+    pub flatpak: Option<IndexMap<String, FlatpakConfiguration>>,
 }
 
-impl Default for Configuration {
-    fn default() -> Self {
+impl Configuration {
+    /// Default configuration for a distribution, `id` and `family` being the
+    /// os-release `ID` and the family it resolves to (see `crosup_core::platform`).
+    pub fn default_for(id: &str, family: &str) -> Self {
         Configuration {
             packages: None,
             install: None,
-            brew: Some(default_brew_install()),
-            git: Some(default_git_install()),
+            distro: None,
+            brew: Some(default_brew_install(family)),
+            git: Some(default_git_install(family)),
             nix: Some(default_nix_install()),
             curl: Some(default_curl_install()),
-            apt: Some(default_apt_install()),
+            apt: Some(default_apt_install(id, family)),
             yum: None,
             dnf: None,
             zypper: None,
@@ -129,7 +222,19 @@ impl Default for Configuration {
             pacman: None,
             emerge: None,
             slackpkg: None,
+            xbps: None,
+            rpm_ostree: None,
             fleek: None,
+            cargo: None,
+            go: None,
+            pipx: None,
+            npm: None,
+            release: None,
+            archive: None,
+            runtime: None,
+            file: None,
+            service: Some(default_service_install(id, family)),
+            flatpak: None,
         }
     }
 }
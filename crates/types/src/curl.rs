@@ -19,6 +19,9 @@ pub struct Script {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postinstall: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_check: Option<String>,
 
@@ -49,18 +52,18 @@ pub fn default_nix_installer() -> Script {
 }
 
 pub fn default_brew_installer() -> Script {
-    let postinstall = match std::env::consts::OS {
-        "macos" => Some("echo 'eval $(/opt/homebrew/bin/brew shellenv)' >> ~/.zprofile".into()),
-        "linux" => {
-            Some("echo 'eval $(/home/linuxbrew/.linuxbrew/bin/brew shellenv)' >> ~/.bashrc".into())
-        }
+    let shell_init = match std::env::consts::OS {
+        "macos" => Some(vec!["eval \"$(/opt/homebrew/bin/brew shellenv)\"".into()]),
+        "linux" => Some(vec![
+            "eval \"$(/home/linuxbrew/.linuxbrew/bin/brew shellenv)\"".into(),
+        ]),
         _ => None,
     };
 
     Script {
         name: "homebrew".into(),
         url: "https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh".into(),
-        postinstall,
+        shell_init,
         version_check: Some("brew".into()),
         env: Some(
             [("NONINTERACTIVE".into(), "true".into())]
@@ -73,6 +76,25 @@ pub fn default_brew_installer() -> Script {
     }
 }
 
+pub fn default_rustup_installer() -> Script {
+    Script {
+        name: "rustup".into(),
+        url: "https://sh.rustup.rs".into(),
+        args: Some("-y".into()),
+        version_check: Some("rustup".into()),
+        ..Default::default()
+    }
+}
+
+pub fn default_mise_installer() -> Script {
+    Script {
+        name: "mise".into(),
+        url: "https://mise.run".into(),
+        version_check: Some("mise".into()),
+        ..Default::default()
+    }
+}
+
 pub fn default_curl_install() -> IndexMap<String, CurlConfiguration> {
     let mut script = IndexMap::new();
     script.insert(
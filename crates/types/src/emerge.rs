@@ -29,4 +29,7 @@ pub struct Package {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verbose: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
 }
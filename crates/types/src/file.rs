@@ -0,0 +1,42 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FileConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_dir: Option<String>,
+
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub dotfile: IndexMap<String, Dotfile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Dotfile {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    pub source: String,
+
+    pub target: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<bool>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub vars: Option<IndexMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+}
@@ -0,0 +1,32 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FlatpakConfiguration {
+    /// Remote apps are installed from, defaults to flathub.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub pkg: IndexMap<String, Package>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Package {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    /// Application ID, e.g. `org.mozilla.firefox`, defaults to the label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    /// Install for the current user only (`flatpak --user`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+}
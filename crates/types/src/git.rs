@@ -1,5 +1,4 @@
 use indexmap::IndexMap;
-use os_release::OsRelease;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -21,6 +20,9 @@ pub struct Repository {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postinstall: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub install_check: Option<String>,
 
@@ -37,7 +39,7 @@ pub struct Repository {
     pub depends_on: Option<Vec<String>>,
 }
 
-pub fn default_git_install() -> IndexMap<String, GitConfiguration> {
+pub fn default_git_install(family: &str) -> IndexMap<String, GitConfiguration> {
     let mut repo = IndexMap::new();
 
     let mut blesh = Repository {
@@ -45,7 +47,10 @@ pub fn default_git_install() -> IndexMap<String, GitConfiguration> {
         url: "https://github.com/akinomyoga/ble.sh.git".into(),
         install: "make -C ble.sh install PREFIX=~/.local".into(),
         preinstall: None,
-        postinstall: Some("echo 'source ~/.local/share/blesh/ble.sh' >> ~/.bashrc".into()),
+        postinstall: None,
+        shell_init: Some(vec![
+            "[ -n \"$BASH_VERSION\" ] && source ~/.local/share/blesh/ble.sh".into(),
+        ]),
         install_check: Some("~/.local/share/blesh/ble.sh".into()),
         recursive: Some(true),
         depth: Some(1),
@@ -53,18 +58,8 @@ pub fn default_git_install() -> IndexMap<String, GitConfiguration> {
         depends_on: None,
     };
 
-    if cfg!(target_os = "linux") {
-        // determine linux distribution using os-release
-        if let Ok(os_release) = OsRelease::new() {
-            let os = os_release.id.to_lowercase();
-            let os = os.as_str();
-            match os {
-                "ubuntu" | "debian" | "linuxmint" | "pop" | "elementary" | "zorin" => {
-                    blesh.preinstall = Some("sudo apt-get install -y gawk build-essential".into());
-                }
-                _ => {}
-            }
-        }
+    if family == "debian" {
+        blesh.preinstall = Some("sudo apt-get install -y gawk build-essential".into());
     }
 
     if cfg!(target_os = "macos") {
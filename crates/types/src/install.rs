@@ -3,13 +3,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::slackpkg;
 
-use super::{apk, apt, brew, dnf, emerge, pacman, yum, zypper};
+use super::{apk, apt, brew, dnf, emerge, pacman, rpm_ostree, xbps, yum, zypper};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct InstallConfiguration {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub packages: Option<Vec<String>>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_manager: Option<String>,
+
     #[serde(serialize_with = "hcl::ser::labeled_block")]
     pub pkg: IndexMap<String, Package>,
 }
@@ -115,8 +118,23 @@ pub struct Package {
     )]
     pub slackpkg: Option<slackpkg::Package>,
 
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub xbps: Option<xbps::Package>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub rpm_ostree: Option<rpm_ostree::Package>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cask: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
 }
 
 impl Into<brew::BrewConfiguration> for InstallConfiguration {
@@ -133,6 +151,7 @@ impl Into<brew::BrewConfiguration> for InstallConfiguration {
         brew::BrewConfiguration {
             pkgs: self.packages,
             pkg,
+            tap: None,
         }
     }
 }
@@ -249,6 +268,34 @@ impl Into<slackpkg::SlackpkgConfiguration> for InstallConfiguration {
     }
 }
 
+impl From<InstallConfiguration> for xbps::XbpsConfiguration {
+    fn from(config: InstallConfiguration) -> Self {
+        let pkg = config
+            .pkg
+            .into_iter()
+            .map(|(name, pkg)| match pkg.xbps {
+                Some(xbps) => (name.clone(), xbps),
+                None => (name.clone(), xbps::Package { name, ..pkg.into() }),
+            })
+            .collect();
+        xbps::XbpsConfiguration { pkg }
+    }
+}
+
+impl From<InstallConfiguration> for rpm_ostree::RpmOstreeConfiguration {
+    fn from(config: InstallConfiguration) -> Self {
+        let pkg = config
+            .pkg
+            .into_iter()
+            .map(|(name, pkg)| match pkg.rpm_ostree {
+                Some(rpm_ostree) => (name.clone(), rpm_ostree),
+                None => (name.clone(), rpm_ostree::Package { name, ..pkg.into() }),
+            })
+            .collect();
+        rpm_ostree::RpmOstreeConfiguration { pkg }
+    }
+}
+
 impl Into<apk::Package> for Package {
     fn into(self) -> apk::Package {
         apk::Package {
@@ -256,6 +303,7 @@ impl Into<apk::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
             interactive: self.interactive,
         }
@@ -274,6 +322,7 @@ impl Into<apt::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
         }
     }
@@ -286,6 +335,7 @@ impl Into<dnf::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
         }
     }
@@ -298,6 +348,7 @@ impl Into<emerge::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
             ask: self.ask,
             verbose: self.verbose,
@@ -312,6 +363,7 @@ impl Into<pacman::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
         }
     }
@@ -324,6 +376,7 @@ impl Into<yum::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
         }
     }
@@ -336,6 +389,7 @@ impl Into<zypper::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
             non_interactive: self.non_interactive,
         }
@@ -348,8 +402,13 @@ impl Into<brew::Package> for Package {
             name: self.name,
             preinstall: self.preinstall,
             postinstall: self.postinstall,
+            shell_init: None,
             version_check: self.version_check,
             cask: self.cask,
+            args: None,
+            head: None,
+            pin: None,
+            tap: None,
         }
     }
 }
@@ -361,7 +420,35 @@ impl Into<slackpkg::Package> for Package {
             packages: self.packages,
             depends_on: self.depends_on,
             postinstall: self.postinstall,
+            shell_init: self.shell_init,
             version_check: self.version_check,
         }
     }
 }
+
+impl From<Package> for xbps::Package {
+    fn from(pkg: Package) -> Self {
+        xbps::Package {
+            name: pkg.name,
+            packages: pkg.packages,
+            depends_on: pkg.depends_on,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init,
+            version_check: pkg.version_check,
+        }
+    }
+}
+
+impl From<Package> for rpm_ostree::Package {
+    fn from(pkg: Package) -> Self {
+        rpm_ostree::Package {
+            name: pkg.name,
+            packages: pkg.packages,
+            depends_on: pkg.depends_on,
+            postinstall: pkg.postinstall,
+            shell_init: pkg.shell_init,
+            version_check: pkg.version_check,
+            apply_live: None,
+        }
+    }
+}
@@ -1,18 +1,30 @@
 pub mod apk;
 pub mod apt;
+pub mod archive;
 pub mod brew;
+pub mod cargo;
 pub mod configuration;
 pub mod curl;
 pub mod dnf;
 pub mod emerge;
+pub mod file;
+pub mod flatpak;
 pub mod fleek;
 pub mod git;
+pub mod go;
 pub mod home_manager;
 pub mod install;
 pub mod inventory;
 pub mod nix;
+pub mod npm;
 pub mod pacman;
+pub mod pipx;
+pub mod release;
+pub mod rpm_ostree;
+pub mod runtime;
+pub mod service;
 pub mod slackpkg;
+pub mod xbps;
 pub mod yum;
 pub mod zypper;
 
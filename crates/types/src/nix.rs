@@ -25,25 +25,65 @@ pub struct Package {
     pub preinstall: Option<String>,
     pub flake: String,
 
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub nix_conf: Option<NixConf>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub depends_on: Option<Vec<String>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_check: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+}
+
+/// Settings merged into `/etc/nix/nix.conf`. List settings are added to the
+/// existing values, `$USER` is replaced by the user running crosup.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NixConf {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub substituters: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted_substituters: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted_public_keys: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted_users: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental_features: Option<Vec<String>>,
+
+    /// Any other setting, written as is (e.g. `max-jobs = "auto"`).
+    #[serde(flatten)]
+    pub settings: IndexMap<String, String>,
 }
 
 pub fn default_nix_install() -> IndexMap<String, NixConfiguration> {
     let mut pkg = IndexMap::new();
-    pkg.insert("flox".into(), 
+    pkg.insert(
+        "flox".into(),
         Package {
             name: "flox".into(),
             impure: Some(true),
             experimental_features: Some("nix-command flakes".into()),
             accept_flake_config: Some(true),
             flake: "github:flox/floxpkgs#flox.fromCatalog".into(),
-            preinstall: Some("echo 'extra-trusted-substituters = https://cache.floxdev.com' | sudo tee -a /etc/nix/nix.conf && echo 'extra-trusted-public-keys = flox-store-public-0:8c/B+kjIaQ+BloCmNkRUKwaVPFWkriSAd0JJvuDu4F0=' | sudo tee -a /etc/nix/nix.conf".into()),
+            nix_conf: Some(NixConf {
+                trusted_substituters: Some(vec!["https://cache.floxdev.com".into()]),
+                trusted_public_keys: Some(vec![
+                    "flox-store-public-0:8c/B+kjIaQ+BloCmNkRUKwaVPFWkriSAd0JJvuDu4F0=".into(),
+                ]),
+                ..Default::default()
+            }),
             ..Default::default()
-        }
+        },
     );
 
     pkg.insert(
@@ -61,12 +101,11 @@ pub fn default_nix_install() -> IndexMap<String, NixConfiguration> {
             name: "devenv".into(),
             accept_flake_config: Some(true),
             flake: "github:cachix/devenv/latest".into(),
-            preinstall: Some(
-                r#"echo "trusted-users = root $USER" | sudo tee -a /etc/nix/nix.conf
-sudo pkill nix-daemon
-cachix use devenv"#
-                    .into(),
-            ),
+            preinstall: Some("cachix use devenv".into()),
+            nix_conf: Some(NixConf {
+                trusted_users: Some(vec!["root".into(), "$USER".into()]),
+                ..Default::default()
+            }),
             depends_on: Some(vec!["cachix".into()]),
             ..Default::default()
         },
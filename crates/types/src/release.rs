@@ -0,0 +1,80 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ReleaseConfiguration {
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub pkg: IndexMap<String, Package>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Package {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    pub repo: String,
+
+    pub tag: String,
+
+    pub asset: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksums: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binaries: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub os_map: Option<IndexMap<String, String>>,
+
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "hcl::ser::block"
+    )]
+    pub arch_map: Option<IndexMap<String, String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postinstall: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_check: Option<String>,
+}
+
+pub fn default_asdf_installer() -> Package {
+    Package {
+        name: "asdf".into(),
+        repo: "asdf-vm/asdf".into(),
+        tag: "v0.16.7".into(),
+        asset: "asdf-{tag}-{os}-{arch}.tar.gz".into(),
+        binaries: Some(vec!["asdf".into()]),
+        arch_map: Some(
+            [
+                ("x86_64".into(), "amd64".into()),
+                ("aarch64".into(), "arm64".into()),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        ),
+        version_check: Some("asdf".into()),
+        ..Default::default()
+    }
+}
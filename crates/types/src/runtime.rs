@@ -0,0 +1,11 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RuntimeConfiguration {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manager: Option<String>,
+
+    #[serde(flatten)]
+    pub versions: IndexMap<String, String>,
+}
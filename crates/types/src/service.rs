@@ -0,0 +1,67 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ServiceConfiguration {
+    #[serde(serialize_with = "hcl::ser::labeled_block")]
+    pub unit: IndexMap<String, Service>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Service {
+    #[serde(skip_serializing, skip_deserializing)]
+    pub name: String,
+
+    /// systemd unit to manage, defaults to the label of the block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<bool>,
+
+    /// Manage a user unit (`systemctl --user`) instead of a system unit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<bool>,
+
+    /// Groups the current user is added to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<Vec<String>>,
+}
+
+pub fn default_service_install(id: &str, family: &str) -> IndexMap<String, ServiceConfiguration> {
+    let mut unit = IndexMap::new();
+
+    if id == "debian" {
+        unit.insert(
+            "docker".into(),
+            Service {
+                name: "docker".into(),
+                groups: Some(vec!["docker".into()]),
+                ..Default::default()
+            },
+        );
+    }
+
+    // libvirt is installed along with minikube on the Debian family
+    if family == "debian" {
+        unit.insert(
+            "minikube".into(),
+            Service {
+                name: "minikube".into(),
+                unit: Some("libvirtd".into()),
+                groups: Some(vec!["libvirt".into()]),
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut service = IndexMap::new();
+    service.insert("install".into(), ServiceConfiguration { unit });
+    service
+}
@@ -23,4 +23,7 @@ pub struct Package {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version_check: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_init: Option<Vec<String>>,
 }